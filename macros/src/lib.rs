@@ -17,22 +17,35 @@ pub fn with_node(_attr: TokenStream, item: TokenStream) -> TokenStream {
         quote! {
             #vis async fn #fn_name(#inputs) #output {
                 let ctx = r2r::Context::create()?;
-                let mut node = r2r::Node::create(ctx, "the_honored_solver", "")?;
 
                 let mut __node_handle: Option<tokio::task::JoinHandle<()>> = None;
+                // set once the body returns, ending the spin loop so its handle can be joined
+                let __node_stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
 
-                let mut start_node = |mut n: r2r::Node| {
-                    let handle = tokio::task::spawn_blocking(move || loop {
-                        n.spin_once(std::time::Duration::from_millis(100));
+                // the node is created by the body so its name and namespace can come from args
+                let mut start_node = |name: &str, namespace: &str| -> r2r::Result<std::sync::Arc<std::sync::Mutex<r2r::Node>>> {
+                    let n = r2r::Node::create(ctx.clone(), name, namespace)?;
+                    let node = std::sync::Arc::new(std::sync::Mutex::new(n));
+                    let spinner = node.clone();
+                    let stop = __node_stop.clone();
+                    let handle = tokio::task::spawn_blocking(move || {
+                        while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                            // hold the node for one short spin only, then sleep without it so
+                            // service calls and resubscriptions can take the lock in between
+                            spinner.lock().unwrap().spin_once(std::time::Duration::from_millis(5));
+                            std::thread::sleep(std::time::Duration::from_millis(5));
+                        }
                     });
                     __node_handle = Some(handle);
+                    Ok(node)
                 };
 
                 let result = (|| async move { #block })().await;
 
-                // if let Some(handle) = __node_handle {
-                //     let _ = handle.await;
-                // }
+                __node_stop.store(true, std::sync::atomic::Ordering::Relaxed);
+                if let Some(handle) = __node_handle {
+                    let _ = handle.await;
+                }
 
                 result
             }
@@ -41,22 +54,35 @@ pub fn with_node(_attr: TokenStream, item: TokenStream) -> TokenStream {
         quote! {
             #vis fn #fn_name(#inputs) #output {
                 let ctx = r2r::Context::create()?;
-                let mut node = r2r::Node::create(ctx, "the_honored_solver", "")?;
 
                 let mut __node_handle: Option<std::thread::JoinHandle<()>> = None;
+                // set once the body returns, ending the spin loop so its handle can be joined
+                let __node_stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
 
-                let mut start_node = |mut n: r2r::Node| {
-                    let handle = std::thread::spawn(move || loop {
-                        n.spin_once(std::time::Duration::from_millis(100));
+                // the node is created by the body so its name and namespace can come from args
+                let mut start_node = |name: &str, namespace: &str| -> r2r::Result<std::sync::Arc<std::sync::Mutex<r2r::Node>>> {
+                    let n = r2r::Node::create(ctx.clone(), name, namespace)?;
+                    let node = std::sync::Arc::new(std::sync::Mutex::new(n));
+                    let spinner = node.clone();
+                    let stop = __node_stop.clone();
+                    let handle = std::thread::spawn(move || {
+                        while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                            // hold the node for one short spin only, then sleep without it so
+                            // service calls and resubscriptions can take the lock in between
+                            spinner.lock().unwrap().spin_once(std::time::Duration::from_millis(5));
+                            std::thread::sleep(std::time::Duration::from_millis(5));
+                        }
                     });
                     __node_handle = Some(handle);
+                    Ok(node)
                 };
 
                 let result = (|| { #block })();
 
-                // if let Some(handle) = __node_handle {
-                //     let _ = handle.join();
-                // }
+                __node_stop.store(true, std::sync::atomic::Ordering::Relaxed);
+                if let Some(handle) = __node_handle {
+                    let _ = handle.join();
+                }
 
                 result
             }
@@ -0,0 +1,71 @@
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use macros::with_node;
+
+/// stands in for the real crate, with just what the expansion calls
+mod r2r {
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    pub struct Error;
+
+    pub type Result<T> = std::result::Result<T, Error>;
+
+    #[derive(Clone)]
+    pub struct Context;
+
+    impl Context {
+        pub fn create() -> Result<Self> {
+            Ok(Self)
+        }
+    }
+
+    pub struct Node {
+        pub spins: usize,
+    }
+
+    impl Node {
+        pub fn create(_ctx: Context, _name: &str, _namespace: &str) -> Result<Self> {
+            Ok(Self { spins: 0 })
+        }
+
+        pub fn spin_once(&mut self, timeout: Duration) {
+            self.spins += 1;
+            std::thread::sleep(timeout);
+        }
+    }
+}
+
+/// waits until the spin loop has run, then hands out only a weak reference to the node
+fn started(node: Arc<Mutex<r2r::Node>>) -> Weak<Mutex<r2r::Node>> {
+    while node.lock().unwrap().spins == 0 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    Arc::downgrade(&node)
+}
+
+#[with_node]
+fn spin_sync() -> Result<Weak<Mutex<r2r::Node>>, r2r::Error> {
+    Ok(started(start_node("test", "")?))
+}
+
+#[with_node]
+async fn spin_async() -> Result<Weak<Mutex<r2r::Node>>, r2r::Error> {
+    Ok(started(start_node("test", "")?))
+}
+
+// the spin loop holds the only other reference to the node, so the node is gone on return
+// only if the loop was stopped and joined before the function returned
+
+#[test]
+fn sync_spin_loop_is_stopped_and_joined() {
+    let node = spin_sync().unwrap();
+    assert!(node.upgrade().is_none());
+}
+
+#[tokio::test]
+async fn async_spin_loop_is_stopped_and_joined() {
+    let node = spin_async().await.unwrap();
+    assert!(node.upgrade().is_none());
+}
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use crate::{
+    maze::{UnboundedMaze, UnboundedPosition},
+    ros::types::{MoveDirection, SensorsStates},
+};
+
+use super::traits::ExplorationAlgorithm;
+
+/// runs a primary strategy and hands control to a fallback for a while whenever the primary loops
+pub struct Composite {
+    primary: Box<dyn ExplorationAlgorithm>,
+    fallback: Box<dyn ExplorationAlgorithm>,
+    fallback_moves: usize,
+    fallback_remaining: usize,
+    /// moves the primary has made since it last took control, a repeat means it is cycling
+    primary_moves: HashSet<(UnboundedPosition, MoveDirection)>,
+    /// whether the fallback chose the last move, so its failure and rationale go back to it
+    fallback_moved: bool,
+}
+
+impl Composite {
+    pub fn new(
+        primary: Box<dyn ExplorationAlgorithm>,
+        fallback: Box<dyn ExplorationAlgorithm>,
+        fallback_moves: usize,
+    ) -> Self {
+        Self {
+            primary,
+            fallback,
+            fallback_moves,
+            fallback_remaining: 0,
+            primary_moves: HashSet::new(),
+            fallback_moved: false,
+        }
+    }
+
+    fn next_fallback_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        sensors: &SensorsStates,
+        maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        let next_move = self.fallback.next_move(current_pos, sensors, maze)?;
+        self.fallback_moved = true;
+
+        if next_move.is_some() {
+            self.fallback_remaining -= 1;
+        } else {
+            log::debug!("{} has nothing left to explore", self.fallback.name());
+            self.fallback_remaining = 0;
+        }
+
+        Ok(next_move)
+    }
+}
+
+impl ExplorationAlgorithm for Composite {
+    fn next_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        sensors: &SensorsStates,
+        maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        if self.fallback_remaining > 0 {
+            log::debug!("active strategy: {}", self.fallback.name());
+            if let Some(direction) = self.next_fallback_move(current_pos, sensors, maze)? {
+                return Ok(Some(direction));
+            }
+
+            // fallback exhausted early, let the primary pick up from here
+            self.primary_moves.clear();
+        }
+
+        log::debug!("active strategy: {}", self.primary.name());
+        self.fallback_moved = false;
+        let Some(direction) = self.primary.next_move(current_pos, sensors, maze)? else {
+            return Ok(None);
+        };
+
+        if self.primary_moves.insert((current_pos, direction)) {
+            return Ok(Some(direction));
+        }
+
+        log::debug!(
+            "{} is looping at {}, switching to {} for {} moves",
+            self.primary.name(),
+            current_pos,
+            self.fallback.name(),
+            self.fallback_moves
+        );
+
+        self.primary_moves.clear();
+        self.fallback_remaining = self.fallback_moves;
+
+        self.next_fallback_move(current_pos, sensors, maze)
+    }
+
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        // only the strategy that chose the move expects to have made it; the other one never
+        // planned it and would undo bookkeeping for a move of its own
+        if self.fallback_moved {
+            self.fallback.on_move_failed(attempted, from);
+        } else {
+            self.primary_moves.remove(&(from, attempted));
+            self.primary.on_move_failed(attempted, from);
+        }
+    }
+
+    fn set_target_hint(&mut self, target: Option<UnboundedPosition>) {
+        self.primary.set_target_hint(target);
+        self.fallback.set_target_hint(target);
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        if self.fallback_moved {
+            self.fallback.move_rationale()
+        } else {
+            self.primary.move_rationale()
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Composite"
+    }
+
+    fn reset(&mut self) {
+        self.primary.reset();
+        self.fallback.reset();
+        self.fallback_remaining = 0;
+        self.primary_moves.clear();
+        self.fallback_moved = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::ros::types::SensorState;
+
+    /// always heads the same way, counting the failures it is told about
+    struct Fixed {
+        direction: MoveDirection,
+        failures: Rc<Cell<usize>>,
+    }
+
+    impl ExplorationAlgorithm for Fixed {
+        fn next_move(
+            &mut self,
+            _current_pos: UnboundedPosition,
+            _sensors: &SensorsStates,
+            _maze: &UnboundedMaze,
+        ) -> eyre::Result<Option<MoveDirection>> {
+            Ok(Some(self.direction))
+        }
+
+        fn on_move_failed(&mut self, _attempted: MoveDirection, _from: UnboundedPosition) {
+            self.failures.set(self.failures.get() + 1);
+        }
+
+        fn name(&self) -> &'static str {
+            "Fixed"
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn fixed(direction: MoveDirection) -> (Box<dyn ExplorationAlgorithm>, Rc<Cell<usize>>) {
+        let failures = Rc::new(Cell::new(0));
+        let explorer = Fixed {
+            direction,
+            failures: failures.clone(),
+        };
+        (Box::new(explorer), failures)
+    }
+
+    #[test]
+    fn loops_hand_over_to_the_fallback_for_a_while() {
+        let (primary, _) = fixed(MoveDirection::Right);
+        let (fallback, _) = fixed(MoveDirection::Down);
+        let mut composite = Composite::new(primary, fallback, 2);
+        let (pos, maze) = (UnboundedPosition::new(0, 0), UnboundedMaze::new());
+        let sensors = SensorsStates::uniform(SensorState::Free);
+
+        let moves: Vec<_> = (0..4)
+            .map(|_| composite.next_move(pos, &sensors, &maze).unwrap())
+            .collect();
+
+        // the repeated right at the same cell is the loop, the fallback then moves twice
+        assert_eq!(
+            moves,
+            [
+                Some(MoveDirection::Right),
+                Some(MoveDirection::Down),
+                Some(MoveDirection::Down),
+                Some(MoveDirection::Right),
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_moves_go_to_the_strategy_that_chose_them() {
+        let (primary, primary_failures) = fixed(MoveDirection::Right);
+        let (fallback, fallback_failures) = fixed(MoveDirection::Down);
+        let mut composite = Composite::new(primary, fallback, 1);
+        let (pos, maze) = (UnboundedPosition::new(0, 0), UnboundedMaze::new());
+        let sensors = SensorsStates::uniform(SensorState::Free);
+
+        let direction = composite.next_move(pos, &sensors, &maze).unwrap().unwrap();
+        composite.on_move_failed(direction, pos);
+        assert_eq!((primary_failures.get(), fallback_failures.get()), (1, 0));
+
+        // the failure forgot the primary's move, so repeating it is not a loop yet
+        composite.next_move(pos, &sensors, &maze).unwrap();
+        let direction = composite.next_move(pos, &sensors, &maze).unwrap().unwrap();
+        assert_eq!(direction, MoveDirection::Down);
+
+        // the fallback's single move used up its turn, but the failure is still its own
+        composite.on_move_failed(direction, pos);
+        assert_eq!((primary_failures.get(), fallback_failures.get()), (1, 1));
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    maze::{Cell, UnboundedMaze, UnboundedPosition},
+    ros::types::{MoveDirection, SensorsStates},
+};
+
+use super::traits::ExplorationAlgorithm;
+
+/// frontier-based exploration: always heads for the nearest known free cell that borders
+/// unknown space, found by bfs over known cells
+///
+/// unlike the backtracker it never walks back to a specific stack entry, it simply goes to
+/// whichever frontier is closest from where it stands, and stops once none is reachable
+pub struct FrontierExploration {
+    rationale: Option<String>,
+}
+
+impl FrontierExploration {
+    pub fn new() -> Self {
+        Self { rationale: None }
+    }
+
+    /// a free cell with at least one orthogonal neighbor nobody has sensed yet
+    fn is_frontier(maze: &UnboundedMaze, pos: UnboundedPosition) -> bool {
+        maze.get(pos) == Cell::Free
+            && pos
+                .neighbors()
+                .any(|(neighbor, _)| !maze.is_explored(neighbor))
+    }
+
+    /// the nearest frontier, its distance and the first move toward it, never crossing the target
+    fn nearest_frontier(
+        maze: &UnboundedMaze,
+        start: UnboundedPosition,
+    ) -> Option<(UnboundedPosition, usize, MoveDirection)> {
+        let mut queue = VecDeque::from([(start, 0)]);
+        let mut first_moves: HashMap<UnboundedPosition, MoveDirection> = HashMap::new();
+        let mut seen = HashSet::from([start]);
+
+        while let Some((current, distance)) = queue.pop_front() {
+            if current != start && Self::is_frontier(maze, current) {
+                return first_moves
+                    .get(&current)
+                    .map(|&direction| (current, distance, direction));
+            }
+
+            for (neighbor, direction) in maze.neighbors(current) {
+                if maze.get(neighbor) == Cell::Target || !seen.insert(neighbor) {
+                    continue;
+                }
+                let first = first_moves.get(&current).copied().unwrap_or(direction);
+                first_moves.insert(neighbor, first);
+                queue.push_back((neighbor, distance + 1));
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for FrontierExploration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExplorationAlgorithm for FrontierExploration {
+    fn next_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        _sensors: &SensorsStates,
+        maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        self.rationale = None;
+
+        let Some((frontier, distance, direction)) = Self::nearest_frontier(maze, current_pos)
+        else {
+            log::debug!("no reachable frontier left");
+            return Ok(None);
+        };
+
+        self.rationale = Some(format!(
+            "frontier: heading {:?} to frontier {} ({} moves away)",
+            direction, frontier, distance
+        ));
+        Ok(Some(direction))
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        self.rationale.clone()
+    }
+
+    fn name(&self) -> &'static str {
+        "Frontier Exploration"
+    }
+
+    fn reset(&mut self) {
+        self.rationale = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{algorithms::exploration::walk::walk, maze::Position};
+
+    /// a corridor from (0, -3) to (0, 2) with the robot at the origin, walled above and below
+    /// and unknown past both ends
+    fn corridor() -> UnboundedMaze {
+        let mut maze = UnboundedMaze::new();
+        for col in -3..=2 {
+            maze.set(UnboundedPosition::new(0, col), Cell::Free);
+            maze.set(UnboundedPosition::new(-1, col), Cell::Blocked);
+            maze.set(UnboundedPosition::new(1, col), Cell::Blocked);
+        }
+        maze.place_robot(UnboundedPosition::new(0, 0));
+        maze
+    }
+
+    #[test]
+    fn frontiers_are_free_cells_next_to_unknown_ones() {
+        let maze = corridor();
+        let at = UnboundedPosition::new;
+
+        assert!(FrontierExploration::is_frontier(&maze, at(0, 2)));
+        assert!(FrontierExploration::is_frontier(&maze, at(0, -3)));
+        assert!(!FrontierExploration::is_frontier(&maze, at(0, 1)));
+        // walls and unknown cells never are, however much unknown space they border
+        assert!(!FrontierExploration::is_frontier(&maze, at(-1, 2)));
+        assert!(!FrontierExploration::is_frontier(&maze, at(0, 3)));
+    }
+
+    #[test]
+    fn heads_for_the_nearest_frontier() {
+        let maze = corridor();
+
+        assert_eq!(
+            FrontierExploration::nearest_frontier(&maze, UnboundedPosition::new(0, 0)),
+            Some((UnboundedPosition::new(0, 2), 2, MoveDirection::Right))
+        );
+        assert_eq!(
+            FrontierExploration::nearest_frontier(&maze, UnboundedPosition::new(0, -1)),
+            Some((UnboundedPosition::new(0, -3), 2, MoveDirection::Left))
+        );
+    }
+
+    #[test]
+    fn stops_once_every_reachable_cell_is_sensed() {
+        let walked = walk(
+            &mut FrontierExploration::new(),
+            "bbbbb\nbrffb\nbfbfb\nbbbbb\n",
+            100,
+        );
+
+        assert!(walked.finished);
+        for free in [(1, 2), (1, 3), (2, 1), (2, 3)] {
+            let free = Position::new(free.0, free.1);
+            let sensed_from_a_neighbor = walked
+                .path
+                .iter()
+                .any(|pos| pos.manhattan_distance(free) <= 1);
+            assert!(sensed_from_a_neighbor, "{} was never sensed", free);
+        }
+    }
+}
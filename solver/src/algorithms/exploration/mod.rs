@@ -1,7 +1,21 @@
+mod composite;
+mod frontier;
+mod pledge;
 mod recursive_backtracker;
+mod spanning_tree;
+mod spiral;
 pub mod traits;
+mod tremaux;
+#[cfg(test)]
+mod walk;
 mod wall_follower;
 
+pub use composite::Composite;
+pub use frontier::FrontierExploration;
+pub use pledge::Pledge;
 pub use recursive_backtracker::RecursiveBacktracker;
+pub use spanning_tree::SpanningTreeCoverage;
+pub use spiral::SpiralExplorer;
 pub use traits::ExplorationAlgorithm;
-pub use wall_follower::WallFollower;
+pub use tremaux::Tremaux;
+pub use wall_follower::{Handedness, WallFollower};
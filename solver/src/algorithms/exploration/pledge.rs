@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    maze::{UnboundedMaze, UnboundedPosition},
+    ros::types::{MoveDirection, SensorState, SensorsStates},
+};
+
+use super::traits::ExplorationAlgorithm;
+
+/// pledge algorithm: heads in a preferred direction and, on hitting a wall, follows it with the
+/// left hand while counting net quarter turns, leaving the wall only once the count is back to
+/// zero and the preferred direction is free
+///
+/// unlike the plain wall follower it cannot get trapped circling a free-standing wall block.
+/// it is an escape strategy rather than a coverage one: it stops as soon as the target is
+/// orthogonally adjacent, or once its walk provably repeats
+pub struct Pledge {
+    preferred: MoveDirection,
+    facing: MoveDirection,
+    /// net quarter turns while following a wall, right turns positive
+    angle: i32,
+    following: bool,
+    /// angle last seen at each wall-following state, to detect walks that can never leave
+    seen: HashMap<(UnboundedPosition, MoveDirection), i32>,
+    rationale: Option<String>,
+    /// facing, angle and following before the last move, restored if that move fails
+    previous: (MoveDirection, i32, bool),
+    /// moves that failed despite the sensors reporting them free
+    blocked_moves: HashSet<(UnboundedPosition, MoveDirection)>,
+    current_pos: Option<UnboundedPosition>,
+}
+
+impl Pledge {
+    /// heads up whenever the turn count allows
+    pub fn new() -> Self {
+        Self::with_preferred(MoveDirection::Up)
+    }
+
+    pub fn with_preferred(preferred: MoveDirection) -> Self {
+        Self {
+            preferred,
+            facing: preferred,
+            angle: 0,
+            following: false,
+            seen: HashMap::new(),
+            rationale: None,
+            previous: (preferred, 0, false),
+            blocked_moves: HashSet::new(),
+            current_pos: None,
+        }
+    }
+
+    fn can_move(&self, direction: MoveDirection, sensors: &SensorsStates) -> bool {
+        if let Some(pos) = self.current_pos
+            && self.blocked_moves.contains(&(pos, direction))
+        {
+            return false;
+        }
+
+        // treat target as blocked during exploration - we don't want to reach it yet
+        sensors.get(direction) == SensorState::Free
+    }
+
+    /// whether this wall-following state was reached before with a turn count no closer to
+    /// zero, in which case the walk repeats forever without ever leaving the wall
+    fn is_stuck(&self, pos: UnboundedPosition) -> bool {
+        self.seen.get(&(pos, self.facing)).is_some_and(|&previous| {
+            self.angle.abs() >= previous.abs()
+                && (previous == 0 || self.angle.signum() == previous.signum())
+        })
+    }
+}
+
+impl Default for Pledge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExplorationAlgorithm for Pledge {
+    fn next_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        sensors: &SensorsStates,
+        _maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        self.current_pos = Some(current_pos);
+        self.previous = (self.facing, self.angle, self.following);
+
+        if MoveDirection::all()
+            .into_iter()
+            .any(|direction| sensors.get(direction) == SensorState::Target)
+        {
+            log::debug!("pledge reached the target at {}", current_pos);
+            return Ok(None);
+        }
+
+        if !self.following {
+            if self.can_move(self.preferred, sensors) {
+                self.facing = self.preferred;
+                self.rationale = Some(format!("pledge: heading {:?} (free)", self.preferred));
+                return Ok(Some(self.preferred));
+            }
+
+            // keep the wall on the left by turning right until something opens up
+            self.following = true;
+            self.facing = self.preferred;
+            for _ in 0..4 {
+                self.facing = self.facing.rotate_right();
+                self.angle += 1;
+                if self.can_move(self.facing, sensors) {
+                    self.rationale = Some(format!(
+                        "pledge: hit a wall, following it {:?} (angle {})",
+                        self.facing, self.angle
+                    ));
+                    return Ok(Some(self.facing));
+                }
+            }
+            eyre::bail!("Completely blocked - no valid moves!");
+        }
+
+        if self.angle == 0 && self.can_move(self.preferred, sensors) {
+            self.following = false;
+            self.facing = self.preferred;
+            self.rationale = Some(format!(
+                "pledge: turn count back to zero, leaving the wall {:?}",
+                self.preferred
+            ));
+            return Ok(Some(self.preferred));
+        }
+
+        if self.is_stuck(current_pos) {
+            log::debug!(
+                "pledge walk repeats at {} facing {:?} (angle {}), stopping",
+                current_pos,
+                self.facing,
+                self.angle
+            );
+            return Ok(None);
+        }
+        self.seen.insert((current_pos, self.facing), self.angle);
+
+        let (next_dir, turn) = [
+            (self.facing.rotate_left(), -1, "turned left"),
+            (self.facing, 0, "went straight"),
+            (self.facing.rotate_right(), 1, "turned right"),
+            (self.facing.opposite(), 2, "turned around"),
+        ]
+        .into_iter()
+        .find(|(direction, _, _)| self.can_move(*direction, sensors))
+        .map(|(direction, turns, description)| {
+            self.angle += turns;
+            (direction, description)
+        })
+        .ok_or_else(|| eyre::eyre!("Completely blocked - no valid moves!"))?;
+
+        self.facing = next_dir;
+        self.rationale = Some(format!("pledge: {} (angle {})", turn, self.angle));
+        Ok(Some(next_dir))
+    }
+
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        self.blocked_moves.insert((from, attempted));
+        (self.facing, self.angle, self.following) = self.previous;
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        self.rationale.clone()
+    }
+
+    fn name(&self) -> &'static str {
+        "Pledge"
+    }
+
+    fn reset(&mut self) {
+        self.facing = self.preferred;
+        self.angle = 0;
+        self.following = false;
+        self.seen.clear();
+        self.rationale = None;
+        self.previous = (self.preferred, 0, false);
+        self.blocked_moves.clear();
+        self.current_pos = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithms::exploration::{
+            WallFollower,
+            walk::{ISLAND, walk},
+        },
+        maze::Position,
+    };
+
+    #[test]
+    fn escapes_the_block_the_wall_follower_circles() {
+        let follower = walk(&mut WallFollower::new(), ISLAND, 200);
+        assert!(follower.finished);
+        assert!(!follower.found_target);
+        // it went once around the block, back past its start, never nearing the top wall
+        let start = follower.path[0];
+        assert_eq!(follower.path.iter().filter(|&&pos| pos == start).count(), 2);
+        assert!(follower.path.iter().all(|pos| pos.row >= 2));
+
+        let pledge = walk(&mut Pledge::new(), ISLAND, 200);
+        assert!(pledge.finished);
+        assert!(pledge.found_target);
+        assert_eq!(pledge.path.last(), Some(&Position::new(1, 5)));
+    }
+}
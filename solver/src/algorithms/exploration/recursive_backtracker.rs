@@ -11,13 +11,22 @@ use super::traits::ExplorationAlgorithm;
 pub struct RecursiveBacktracker {
     visited: HashSet<UnboundedPosition>,
     path_stack: VecDeque<UnboundedPosition>,
+    /// order unvisited neighbors are tried in, kept across resets
+    priority: [MoveDirection; 4],
+    /// where the target was spotted; once known, the unvisited neighbor closest to it goes first
+    target_hint: Option<UnboundedPosition>,
+    rationale: Option<String>,
 }
 
 impl RecursiveBacktracker {
-    pub fn new() -> Self {
+    /// `priority` must name every direction once, earlier ones are tried first
+    pub fn with_priority(priority: [MoveDirection; 4]) -> Self {
         Self {
             visited: HashSet::new(),
             path_stack: VecDeque::new(),
+            priority,
+            target_hint: None,
+            rationale: None,
         }
     }
 
@@ -30,38 +39,23 @@ impl RecursiveBacktracker {
 
         let mut unvisited = Vec::new();
 
-        let directions = [
-            (sensors.up, MoveDirection::Up, current.row - 1, current.col),
-            (
-                sensors.down,
-                MoveDirection::Down,
-                current.row + 1,
-                current.col,
-            ),
-            (
-                sensors.left,
-                MoveDirection::Left,
-                current.row,
-                current.col - 1,
-            ),
-            (
-                sensors.right,
-                MoveDirection::Right,
-                current.row,
-                current.col + 1,
-            ),
-        ];
-
-        for (state, direction, row, col) in directions {
+        // no need to deprioritize the reverse of the last move: it leads back to the cell we
+        // just left, which `next_move` marked visited, so it never shows up here
+        for direction in self.priority {
             // treat target as blocked during exploration - we don't want to reach it yet
-            if matches!(state, SensorState::Free) {
-                let pos = UnboundedPosition::new(row, col);
+            if matches!(sensors.get(direction), SensorState::Free) {
+                let pos = current.move_in_direction(direction);
                 if !self.visited.contains(&pos) {
                     unvisited.push((pos, direction));
                 }
             }
         }
 
+        // stable, so neighbors equally close to the target keep the priority order
+        if let Some(target) = self.target_hint {
+            unvisited.sort_by_key(|(pos, _)| pos.manhattan_distance(target));
+        }
+
         unvisited
     }
 
@@ -114,23 +108,22 @@ impl ExplorationAlgorithm for RecursiveBacktracker {
         maze: &UnboundedMaze,
     ) -> eyre::Result<Option<MoveDirection>> {
         self.visited.insert(current_pos);
+        self.rationale = None;
 
         let unvisited_neighbors = self.get_unvisited_neighbors(current_pos, sensors);
 
         if !unvisited_neighbors.is_empty() {
             let (_next_pos, direction) = unvisited_neighbors[0];
             self.path_stack.push_back(current_pos);
+            self.rationale = Some(format!("backtracker: unvisited neighbor {:?}", direction));
             return Ok(Some(direction));
         }
 
         if let Some(backtrack_target) = self.path_stack.pop_back() {
-            log::debug!(
-                "backtracking to ({}, {})",
-                backtrack_target.row,
-                backtrack_target.col
-            );
+            log::debug!("backtracking to {}", backtrack_target);
 
             if let Some(first_move) = self.find_path_bfs(maze, current_pos, backtrack_target) {
+                self.rationale = Some(format!("backtracker: backtracking to {}", backtrack_target));
                 return Ok(Some(first_move));
             }
         }
@@ -138,6 +131,22 @@ impl ExplorationAlgorithm for RecursiveBacktracker {
         Ok(None)
     }
 
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        // the failed attempt pushed `from` expecting to advance, undo that and never retry the cell
+        if self.path_stack.back() == Some(&from) {
+            self.path_stack.pop_back();
+        }
+        self.visited.insert(from.move_in_direction(attempted));
+    }
+
+    fn set_target_hint(&mut self, target: Option<UnboundedPosition>) {
+        self.target_hint = target;
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        self.rationale.clone()
+    }
+
     fn name(&self) -> &'static str {
         "Recursive Backtracker"
     }
@@ -145,5 +154,91 @@ impl ExplorationAlgorithm for RecursiveBacktracker {
     fn reset(&mut self) {
         self.visited.clear();
         self.path_stack.clear();
+        self.target_hint = None;
+        self.rationale = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ros::types::SensorState;
+
+    #[test]
+    fn priority_picks_the_first_free_direction() {
+        use MoveDirection::{Down, Left, Right, Up};
+
+        let (origin, maze) = (UnboundedPosition::new(0, 0), UnboundedMaze::new());
+        let mut sensors = SensorsStates::uniform(SensorState::Free);
+
+        let mut default_order = RecursiveBacktracker::with_priority(MoveDirection::all());
+        let mut rightward = RecursiveBacktracker::with_priority([Right, Up, Down, Left]);
+        assert_eq!(
+            default_order.next_move(origin, &sensors, &maze).unwrap(),
+            Some(Up)
+        );
+        assert_eq!(
+            rightward.next_move(origin, &sensors, &maze).unwrap(),
+            Some(Right)
+        );
+
+        // a reset forgets the walk but keeps the order, and walls are skipped
+        rightward.reset();
+        sensors.set(Right, SensorState::Blocked);
+        assert_eq!(
+            rightward.next_move(origin, &sensors, &maze).unwrap(),
+            Some(Up)
+        );
+    }
+
+    #[test]
+    fn never_steps_straight_back_while_a_new_cell_is_open() {
+        use MoveDirection::{Down, Left, Right, Up};
+
+        // down comes first, so only the visited check keeps it from undoing the move up
+        let mut backtracker = RecursiveBacktracker::with_priority([Down, Up, Left, Right]);
+        let mut maze = UnboundedMaze::new();
+        let mut step = |pos: UnboundedPosition, sensors: SensorsStates| {
+            maze.update_from_sensors(pos, &sensors);
+            backtracker.next_move(pos, &sensors, &maze).unwrap()
+        };
+
+        let mut origin = SensorsStates::uniform(SensorState::Free);
+        origin.set(Down, SensorState::Blocked);
+        assert_eq!(step(UnboundedPosition::new(0, 0), origin), Some(Up));
+
+        let open = SensorsStates::uniform(SensorState::Free);
+        assert_eq!(step(UnboundedPosition::new(-1, 0), open), Some(Up));
+
+        // a dead end leaves only the way back, which is taken as before
+        let mut dead_end = SensorsStates::uniform(SensorState::Blocked);
+        dead_end.set(Down, SensorState::Free);
+        assert_eq!(step(UnboundedPosition::new(-2, 0), dead_end), Some(Down));
+    }
+
+    #[test]
+    fn target_hint_steers_toward_the_target() {
+        let (origin, maze) = (UnboundedPosition::new(0, 0), UnboundedMaze::new());
+        let sensors = SensorsStates::uniform(SensorState::Free);
+        let first_move = |hint| {
+            let mut backtracker = RecursiveBacktracker::with_priority(MoveDirection::all());
+            backtracker.set_target_hint(hint);
+            backtracker.next_move(origin, &sensors, &maze).unwrap()
+        };
+
+        assert_eq!(first_move(None), Some(MoveDirection::Up));
+        assert_eq!(
+            first_move(Some(UnboundedPosition::new(0, 5))),
+            Some(MoveDirection::Right)
+        );
+        assert_eq!(
+            first_move(Some(UnboundedPosition::new(4, -1))),
+            Some(MoveDirection::Down)
+        );
+        // up and right end up equally close, so the priority order breaks the tie
+        assert_eq!(
+            first_move(Some(UnboundedPosition::new(-3, 3))),
+            Some(MoveDirection::Up)
+        );
     }
 }
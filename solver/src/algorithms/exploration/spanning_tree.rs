@@ -0,0 +1,163 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    maze::{Cell, UnboundedMaze, UnboundedPosition},
+    ros::types::{MoveDirection, SensorState, SensorsStates},
+};
+
+use super::traits::ExplorationAlgorithm;
+
+/// online spanning-tree coverage
+///
+/// every free cell is attached to the tree the first time it is sensed. the walk descends
+/// into the branch with the fewest open continuations first, so short side branches are
+/// finished before the robot moves on, and once a branch is exhausted it jumps to the
+/// nearest pending cell through any known cell instead of retracing the tree edge by edge
+pub struct SpanningTreeCoverage {
+    visited: HashSet<UnboundedPosition>,
+    /// sensed free cells not walked yet
+    pending: HashSet<UnboundedPosition>,
+    /// tree edges, child → parent
+    parent: HashMap<UnboundedPosition, UnboundedPosition>,
+    rationale: Option<String>,
+}
+
+impl SpanningTreeCoverage {
+    pub fn new() -> Self {
+        Self {
+            visited: HashSet::new(),
+            pending: HashSet::new(),
+            parent: HashMap::new(),
+            rationale: None,
+        }
+    }
+
+    /// free orthogonal cells around `pos` that have not been walked yet
+    fn open_children(
+        &self,
+        pos: UnboundedPosition,
+        maze: &UnboundedMaze,
+    ) -> Vec<(UnboundedPosition, MoveDirection)> {
+        pos.neighbors()
+            .filter(|(neighbor, _)| {
+                maze.get(*neighbor) == Cell::Free && !self.visited.contains(neighbor)
+            })
+            .collect()
+    }
+
+    /// how many ways a branch could continue past `pos`, unknown cells counted as open
+    fn continuations(&self, pos: UnboundedPosition, maze: &UnboundedMaze) -> usize {
+        pos.neighbors()
+            .filter(|(neighbor, _)| {
+                !self.visited.contains(neighbor)
+                    && matches!(maze.get(*neighbor), Cell::Free | Cell::Unknown)
+            })
+            .count()
+    }
+
+    /// first move of the shortest known route to the nearest pending cell, never crossing the target
+    fn route_to_pending(
+        &self,
+        maze: &UnboundedMaze,
+        start: UnboundedPosition,
+    ) -> Option<(UnboundedPosition, MoveDirection)> {
+        let mut queue = VecDeque::new();
+        let mut first_moves: HashMap<UnboundedPosition, MoveDirection> = HashMap::new();
+        let mut seen = HashSet::from([start]);
+
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            if current != start && self.pending.contains(&current) {
+                return first_moves.get(&current).map(|&dir| (current, dir));
+            }
+
+            for (neighbor, direction) in maze.neighbors(current) {
+                if maze.get(neighbor) == Cell::Target || !seen.insert(neighbor) {
+                    continue;
+                }
+                let first = first_moves.get(&current).copied().unwrap_or(direction);
+                first_moves.insert(neighbor, first);
+                queue.push_back(neighbor);
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for SpanningTreeCoverage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExplorationAlgorithm for SpanningTreeCoverage {
+    fn next_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        sensors: &SensorsStates,
+        maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        self.visited.insert(current_pos);
+        self.pending.remove(&current_pos);
+        self.rationale = None;
+
+        for direction in MoveDirection::all() {
+            let neighbor = current_pos.move_in_direction(direction);
+            if sensors.get(direction) == SensorState::Free && !self.visited.contains(&neighbor) {
+                self.pending.insert(neighbor);
+                self.parent.entry(neighbor).or_insert(current_pos);
+            }
+        }
+
+        let children = self.open_children(current_pos, maze);
+        if let Some(&(child, direction)) = children
+            .iter()
+            .min_by_key(|(child, _)| self.continuations(*child, maze))
+        {
+            self.rationale = Some(format!(
+                "spanning tree: descending {:?} into the branch with {} continuations",
+                direction,
+                self.continuations(child, maze)
+            ));
+            return Ok(Some(direction));
+        }
+
+        if let Some((goal, direction)) = self.route_to_pending(maze, current_pos) {
+            self.rationale = Some(format!(
+                "spanning tree: branch exhausted, heading to {} (tree parent {})",
+                goal,
+                self.parent
+                    .get(&goal)
+                    .map_or_else(|| "none".to_string(), ToString::to_string)
+            ));
+            return Ok(Some(direction));
+        }
+
+        log::debug!("spanning tree covers {} cells", self.visited.len());
+        Ok(None)
+    }
+
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        let blocked = from.move_in_direction(attempted);
+        self.pending.remove(&blocked);
+        self.parent.remove(&blocked);
+        self.visited.insert(blocked);
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        self.rationale.clone()
+    }
+
+    fn name(&self) -> &'static str {
+        "Spanning Tree Coverage"
+    }
+
+    fn reset(&mut self) {
+        self.visited.clear();
+        self.pending.clear();
+        self.parent.clear();
+        self.rationale = None;
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    maze::{Cell, UnboundedMaze, UnboundedPosition},
+    ros::types::{MoveDirection, SensorsStates},
+};
+
+use super::traits::ExplorationAlgorithm;
+
+/// leg directions of the spiral, turning clockwise
+const SPIRAL_TURNS: [MoveDirection; 4] = [
+    MoveDirection::Right,
+    MoveDirection::Down,
+    MoveDirection::Left,
+    MoveDirection::Up,
+];
+
+/// outward square spiral around the start (right, down, left, up with legs of 1, 1, 2, 2, 3, ...)
+///
+/// heads for the earliest spiral cell that is known free, unvisited and reachable. blocked or
+/// unreachable spiral cells are skipped rather than waited on, so the walk degrades to the
+/// next reachable cell along the spiral and never gets stuck while any known cell is open
+pub struct SpiralExplorer {
+    visited: HashSet<UnboundedPosition>,
+    /// spiral cells in order, generated as the explored region grows
+    spiral: Vec<UnboundedPosition>,
+    /// every spiral cell before this index is visited or blocked
+    cursor: usize,
+    leg: usize,
+    leg_progress: usize,
+    rationale: Option<String>,
+}
+
+impl SpiralExplorer {
+    pub fn new() -> Self {
+        Self {
+            visited: HashSet::new(),
+            spiral: vec![UnboundedPosition::new(0, 0)],
+            cursor: 0,
+            leg: 0,
+            leg_progress: 0,
+            rationale: None,
+        }
+    }
+
+    /// grows the spiral until it covers every cell within `radius` of the start
+    fn extend_spiral(&mut self, radius: usize) {
+        let cells = (2 * radius + 1).pow(2);
+
+        while self.spiral.len() < cells {
+            let last = self.spiral[self.spiral.len() - 1];
+            self.spiral
+                .push(last.move_in_direction(SPIRAL_TURNS[self.leg % 4]));
+
+            self.leg_progress += 1;
+            // legs grow by one every two turns
+            if self.leg_progress == self.leg / 2 + 1 {
+                self.leg += 1;
+                self.leg_progress = 0;
+            }
+        }
+    }
+
+    fn is_open(&self, pos: UnboundedPosition, maze: &UnboundedMaze) -> bool {
+        maze.get(pos) == Cell::Free && !self.visited.contains(&pos)
+    }
+
+    /// first move towards every cell reachable over known cells, never crossing the target
+    fn first_moves(
+        maze: &UnboundedMaze,
+        start: UnboundedPosition,
+    ) -> HashMap<UnboundedPosition, MoveDirection> {
+        let mut first_moves = HashMap::new();
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(current) = queue.pop_front() {
+            for (neighbor, direction) in maze.neighbors(current) {
+                if maze.get(neighbor) == Cell::Target || !seen.insert(neighbor) {
+                    continue;
+                }
+                let first = first_moves.get(&current).copied().unwrap_or(direction);
+                first_moves.insert(neighbor, first);
+                queue.push_back(neighbor);
+            }
+        }
+
+        first_moves
+    }
+}
+
+impl Default for SpiralExplorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExplorationAlgorithm for SpiralExplorer {
+    fn next_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        _sensors: &SensorsStates,
+        maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        self.visited.insert(current_pos);
+        self.rationale = None;
+
+        let Some((min_row, max_row, min_col, max_col)) = maze.get_bounds() else {
+            return Ok(None);
+        };
+        let radius = [min_row, max_row, min_col, max_col]
+            .into_iter()
+            .map(isize::unsigned_abs)
+            .max()
+            .unwrap_or(0);
+        self.extend_spiral(radius);
+
+        while let Some(&pos) = self.spiral.get(self.cursor) {
+            let resolved = self.visited.contains(&pos)
+                || matches!(maze.get(pos), Cell::Blocked | Cell::Target);
+            if !resolved {
+                break;
+            }
+            self.cursor += 1;
+        }
+
+        // the spiral covers the whole known region, so no reachable open cell is missed here
+        let first_moves = Self::first_moves(maze, current_pos);
+        let goal = self.spiral[self.cursor..]
+            .iter()
+            .enumerate()
+            .find(|(_, pos)| self.is_open(**pos, maze) && first_moves.contains_key(pos));
+
+        let Some((offset, &goal)) = goal else {
+            return Ok(None);
+        };
+        self.rationale = Some(if offset == 0 {
+            format!("spiral: next spiral cell {}", goal)
+        } else {
+            format!(
+                "spiral: skipping {} blocked or unreachable spiral cells to {}",
+                offset, goal
+            )
+        });
+        Ok(first_moves.get(&goal).copied())
+    }
+
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        self.visited.insert(from.move_in_direction(attempted));
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        self.rationale.clone()
+    }
+
+    fn name(&self) -> &'static str {
+        "Spiral"
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
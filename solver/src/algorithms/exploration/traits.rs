@@ -12,7 +12,51 @@ pub trait ExplorationAlgorithm {
         maze: &UnboundedMaze,
     ) -> eyre::Result<Option<MoveDirection>>;
 
+    /// called when the move returned by `next_move` failed, meaning the cell in `attempted`
+    /// direction from `from` is actually a wall
+    fn on_move_failed(&mut self, _attempted: MoveDirection, _from: UnboundedPosition) {}
+
+    /// where the target was last spotted, `None` while it is unknown; explorers may use it to
+    /// steer and are free to ignore it
+    fn set_target_hint(&mut self, _target: Option<UnboundedPosition>) {}
+
+    /// human-readable reason for the most recent move, if the explorer records one
+    fn move_rationale(&self) -> Option<String> {
+        None
+    }
+
     fn name(&self) -> &'static str;
 
     fn reset(&mut self);
 }
+
+impl<T: ExplorationAlgorithm + ?Sized> ExplorationAlgorithm for Box<T> {
+    fn next_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        sensors: &SensorsStates,
+        maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        (**self).next_move(current_pos, sensors, maze)
+    }
+
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        (**self).on_move_failed(attempted, from)
+    }
+
+    fn set_target_hint(&mut self, target: Option<UnboundedPosition>) {
+        (**self).set_target_hint(target)
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        (**self).move_rationale()
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
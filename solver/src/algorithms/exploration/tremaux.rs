@@ -0,0 +1,183 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    maze::{UnboundedMaze, UnboundedPosition},
+    ros::types::{MoveDirection, SensorState, SensorsStates},
+};
+
+use super::traits::ExplorationAlgorithm;
+
+/// trémaux's algorithm: marks every passage each time it is walked and never walks one a third
+/// time, preferring unmarked passages over once-marked ones
+///
+/// arriving over a fresh passage at a cell already seen closes a loop, so it turns straight
+/// back. that keeps the walk a depth-first search which cannot circle islands the way the wall
+/// follower does. like pledge it stops as soon as the target is orthogonally adjacent, or once
+/// every reachable passage has been walked twice
+pub struct Tremaux {
+    /// times each passage was walked, keyed by its two cells in either order
+    marks: HashMap<(UnboundedPosition, UnboundedPosition), u8>,
+    visited: HashSet<UnboundedPosition>,
+    /// cell the last move left, consumed by the next call
+    came_from: Option<UnboundedPosition>,
+    /// `came_from` and whether the cell was new before the last move, restored if it fails
+    previous: (Option<UnboundedPosition>, bool),
+    /// moves that failed despite the sensors reporting them free
+    blocked_moves: HashSet<(UnboundedPosition, MoveDirection)>,
+    rationale: Option<String>,
+}
+
+impl Tremaux {
+    pub fn new() -> Self {
+        Self {
+            marks: HashMap::new(),
+            visited: HashSet::new(),
+            came_from: None,
+            previous: (None, false),
+            blocked_moves: HashSet::new(),
+            rationale: None,
+        }
+    }
+
+    fn marks(&self, a: UnboundedPosition, b: UnboundedPosition) -> u8 {
+        self.marks.get(&(a, b)).copied().unwrap_or(0)
+    }
+
+    /// adds `delta` marks to the passage between `a` and `b`, in both directions
+    fn mark(&mut self, a: UnboundedPosition, b: UnboundedPosition, delta: i8) {
+        let marks = self.marks(a, b).saturating_add_signed(delta);
+        self.marks.insert((a, b), marks);
+        self.marks.insert((b, a), marks);
+    }
+}
+
+impl Default for Tremaux {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExplorationAlgorithm for Tremaux {
+    fn next_move(
+        &mut self,
+        current_pos: UnboundedPosition,
+        sensors: &SensorsStates,
+        _maze: &UnboundedMaze,
+    ) -> eyre::Result<Option<MoveDirection>> {
+        self.rationale = None;
+
+        if MoveDirection::all()
+            .into_iter()
+            .any(|direction| sensors.get(direction) == SensorState::Target)
+        {
+            log::debug!("tremaux reached the target at {}", current_pos);
+            return Ok(None);
+        }
+
+        let first_visit = self.visited.insert(current_pos);
+        let entry = self.came_from.take();
+        self.previous = (entry, first_visit);
+
+        // treat target as blocked during exploration - we don't want to reach it yet
+        let passages: Vec<(MoveDirection, UnboundedPosition, u8)> = MoveDirection::all()
+            .into_iter()
+            .filter(|&direction| {
+                sensors.get(direction) == SensorState::Free
+                    && !self.blocked_moves.contains(&(current_pos, direction))
+            })
+            .map(|direction| {
+                let next = current_pos.move_in_direction(direction);
+                (direction, next, self.marks(current_pos, next))
+            })
+            .filter(|&(_, _, marks)| marks < 2)
+            .collect();
+
+        let loop_closed = entry
+            .filter(|&prev| !first_visit && self.marks(current_pos, prev) == 1)
+            .and_then(|prev| passages.iter().find(|&&(_, next, _)| next == prev));
+
+        let (direction, next, _) = match loop_closed {
+            Some(&passage) => {
+                self.rationale = Some(format!(
+                    "tremaux: {} was already visited, turning back {:?}",
+                    current_pos, passage.0
+                ));
+                passage
+            }
+            None => {
+                let Some(&passage) = passages.iter().min_by_key(|&&(_, _, marks)| marks) else {
+                    log::debug!("tremaux walked every passage twice, stopping");
+                    return Ok(None);
+                };
+                self.rationale = Some(format!(
+                    "tremaux: passage {:?} marked {} time(s)",
+                    passage.0, passage.2
+                ));
+                passage
+            }
+        };
+
+        self.mark(current_pos, next, 1);
+        self.came_from = Some(current_pos);
+        Ok(Some(direction))
+    }
+
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        self.blocked_moves.insert((from, attempted));
+        self.mark(from, from.move_in_direction(attempted), -1);
+
+        let (entry, first_visit) = self.previous;
+        self.came_from = entry;
+        if first_visit {
+            self.visited.remove(&from);
+        }
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        self.rationale.clone()
+    }
+
+    fn name(&self) -> &'static str {
+        "Tremaux"
+    }
+
+    fn reset(&mut self) {
+        self.marks.clear();
+        self.visited.clear();
+        self.came_from = None;
+        self.previous = (None, false);
+        self.blocked_moves.clear();
+        self.rationale = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithms::exploration::{
+            WallFollower,
+            walk::{ISLAND, walk},
+        },
+        maze::Position,
+    };
+
+    #[test]
+    fn reaches_the_target_around_a_loop_marking_no_passage_thrice() {
+        // the wall follower only circles the block
+        assert!(!walk(&mut WallFollower::new(), ISLAND, 500).found_target);
+        let mut tremaux = Tremaux::new();
+
+        let walked = walk(&mut tremaux, ISLAND, 500);
+
+        assert!(walked.finished);
+        assert!(walked.found_target);
+        assert_eq!(walked.path.last(), Some(&Position::new(1, 5)));
+        assert!(!tremaux.marks.is_empty());
+        assert!(
+            tremaux.marks.values().all(|&marks| marks <= 2),
+            "{:?}",
+            tremaux.marks
+        );
+    }
+}
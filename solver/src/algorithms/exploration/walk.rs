@@ -0,0 +1,102 @@
+use crate::{
+    maze::{BoundedMaze, Cell, Position, UnboundedMaze, UnboundedPosition},
+    ros::types::{MoveDirection, SensorState, SensorsStates},
+};
+
+use super::traits::ExplorationAlgorithm;
+
+/// an open room with a free-standing block; the robot starts against the block's bottom
+/// and the target sits in a notch of the top wall
+pub(super) const ISLAND: &str = "\
+bbbbbtbbb
+bfffffffb
+bfffffffb
+bffbbbffb
+bffbbbffb
+bfffrfffb
+bfffffffb
+bbbbbbbbb
+";
+
+/// how an explorer's walk over a known maze went
+pub(super) struct Walk {
+    /// every cell the robot stood on, the start included
+    pub path: Vec<Position>,
+    /// the explorer reported it was done before running out of moves
+    pub finished: bool,
+    /// a cardinal sensor read the target at some point
+    pub found_target: bool,
+}
+
+/// drives `explorer` from the maze's robot cell for at most `max_moves` moves, with sensor
+/// readings taken straight off the grid the way the simulator reports them
+pub(super) fn walk(
+    explorer: &mut impl ExplorationAlgorithm,
+    ascii: &str,
+    max_moves: usize,
+) -> Walk {
+    let grid = BoundedMaze::from_ascii(ascii).unwrap();
+    let start = grid.find_robot().unwrap();
+    let unbounded = |pos: Position| {
+        UnboundedPosition::new(
+            pos.row as isize - start.row as isize,
+            pos.col as isize - start.col as isize,
+        )
+    };
+
+    let mut maze = UnboundedMaze::new();
+    let mut walk = Walk {
+        path: vec![start],
+        finished: false,
+        found_target: false,
+    };
+    let mut current = start;
+
+    for _ in 0..max_moves {
+        let sensors = sensors_at(&grid, current);
+        walk.found_target |= MoveDirection::all()
+            .into_iter()
+            .any(|direction| sensors.get(direction) == SensorState::Target);
+        maze.update_from_sensors(unbounded(current), &sensors);
+
+        let Some(direction) = explorer
+            .next_move(unbounded(current), &sensors, &maze)
+            .unwrap()
+        else {
+            walk.finished = true;
+            break;
+        };
+
+        match current
+            .move_in_direction(direction, grid.bounds())
+            .filter(|&next| grid.is_walkable(next))
+        {
+            Some(next) => {
+                current = next;
+                walk.path.push(next);
+            }
+            None => explorer.on_move_failed(direction, unbounded(current)),
+        }
+    }
+
+    walk
+}
+
+fn sensors_at(grid: &BoundedMaze, pos: Position) -> SensorsStates {
+    let mut sensors = SensorsStates::uniform(SensorState::Blocked);
+    for direction in MoveDirection::all()
+        .into_iter()
+        .chain(MoveDirection::diagonals())
+    {
+        let state = match pos
+            .move_in_direction(direction, grid.bounds())
+            .and_then(|next| grid.get(next))
+        {
+            Some(Cell::Target) => SensorState::Target,
+            Some(cell) if cell.is_walkable() => SensorState::Free,
+            _ => SensorState::Blocked,
+        };
+        sensors.set(direction, state);
+    }
+    sensors
+}
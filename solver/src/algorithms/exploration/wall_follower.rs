@@ -2,70 +2,79 @@ use std::collections::HashSet;
 
 use crate::{
     maze::{UnboundedMaze, UnboundedPosition},
-    ros::types::{MoveDirection, SensorsStates},
+    ros::types::{MoveDirection, SensorState, SensorsStates},
 };
 
 use super::traits::ExplorationAlgorithm;
 
-/// wall follower using left-hand rule
+/// which wall the follower keeps its hand on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Handedness {
+    #[default]
+    Left,
+    Right,
+}
+
+/// wall follower using the left- or right-hand rule
 pub struct WallFollower {
+    handedness: Handedness,
     facing: MoveDirection,
     first_move: bool,
     visited: HashSet<UnboundedPosition>,
     start_pos: Option<UnboundedPosition>,
     returned_to_start: bool,
+    rationale: Option<String>,
+    /// facing before the last move, restored if that move fails
+    previous_facing: MoveDirection,
+    /// moves that failed despite the sensors reporting them free
+    blocked_moves: HashSet<(UnboundedPosition, MoveDirection)>,
+    current_pos: Option<UnboundedPosition>,
 }
 
 impl WallFollower {
+    /// left-hand rule
     pub fn new() -> Self {
+        Self::with_handedness(Handedness::Left)
+    }
+
+    pub fn with_handedness(handedness: Handedness) -> Self {
         Self {
+            handedness,
             facing: MoveDirection::Up,
             first_move: true,
             visited: HashSet::new(),
             start_pos: None,
             returned_to_start: false,
+            rationale: None,
+            previous_facing: MoveDirection::Up,
+            blocked_moves: HashSet::new(),
+            current_pos: None,
         }
     }
 
     fn turn_left(&self) -> MoveDirection {
-        match self.facing {
-            MoveDirection::Up => MoveDirection::Left,
-            MoveDirection::Left => MoveDirection::Down,
-            MoveDirection::Down => MoveDirection::Right,
-            MoveDirection::Right => MoveDirection::Up,
-        }
+        self.facing.rotate_left()
     }
 
     fn turn_right(&self) -> MoveDirection {
-        match self.facing {
-            MoveDirection::Up => MoveDirection::Right,
-            MoveDirection::Right => MoveDirection::Down,
-            MoveDirection::Down => MoveDirection::Left,
-            MoveDirection::Left => MoveDirection::Up,
-        }
-    }
-
-    fn turn_around(&self) -> MoveDirection {
-        match self.facing {
-            MoveDirection::Up => MoveDirection::Down,
-            MoveDirection::Down => MoveDirection::Up,
-            MoveDirection::Left => MoveDirection::Right,
-            MoveDirection::Right => MoveDirection::Left,
-        }
+        self.facing.rotate_right()
     }
 
     fn can_move(&self, direction: MoveDirection, sensors: &SensorsStates) -> bool {
-        use crate::ros::types::SensorState;
-
-        let sensor_state = match direction {
-            MoveDirection::Up => &sensors.up,
-            MoveDirection::Down => &sensors.down,
-            MoveDirection::Left => &sensors.left,
-            MoveDirection::Right => &sensors.right,
-        };
+        if let Some(pos) = self.current_pos
+            && self.blocked_moves.contains(&(pos, direction))
+        {
+            return false;
+        }
 
         // treat target as blocked during exploration - we don't want to reach it yet
-        matches!(sensor_state, SensorState::Free)
+        sensors.get(direction) == SensorState::Free
+    }
+}
+
+impl Default for WallFollower {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -83,6 +92,8 @@ impl ExplorationAlgorithm for WallFollower {
 
         // mark current position as visited
         self.visited.insert(current_pos);
+        self.current_pos = Some(current_pos);
+        self.previous_facing = self.facing;
 
         // if we've returned to start after visiting other positions, exploration is complete
         if self.returned_to_start {
@@ -102,43 +113,71 @@ impl ExplorationAlgorithm for WallFollower {
 
         if self.first_move {
             self.first_move = false;
-            for dir in [
-                MoveDirection::Up,
-                MoveDirection::Right,
-                MoveDirection::Down,
-                MoveDirection::Left,
-            ] {
+            let sweep = match self.handedness {
+                Handedness::Left => [
+                    MoveDirection::Up,
+                    MoveDirection::Right,
+                    MoveDirection::Down,
+                    MoveDirection::Left,
+                ],
+                Handedness::Right => [
+                    MoveDirection::Up,
+                    MoveDirection::Left,
+                    MoveDirection::Down,
+                    MoveDirection::Right,
+                ],
+            };
+            for dir in sweep {
                 if self.can_move(dir, sensors) {
                     self.facing = dir;
+                    self.rationale = Some(format!("wall follower: initial move {:?} (free)", dir));
                     return Ok(Some(dir));
                 }
             }
             eyre::bail!("No valid initial move - completely surrounded!");
         }
 
-        let left = self.turn_left();
+        let left = (self.turn_left(), "turned left");
+        let right = (self.turn_right(), "turned right");
+        // hand side first, then straight, then the far side
+        let (hand, far) = match self.handedness {
+            Handedness::Left => (left, right),
+            Handedness::Right => (right, left),
+        };
         let straight = self.facing;
-        let right = self.turn_right();
-        let back = self.turn_around();
+        let back = self.facing.opposite();
 
-        let next_dir = if self.can_move(left, sensors) {
-            left
+        let (next_dir, turn) = if self.can_move(hand.0, sensors) {
+            hand
         } else if self.can_move(straight, sensors) {
-            straight
-        } else if self.can_move(right, sensors) {
-            right
+            (straight, "went straight")
+        } else if self.can_move(far.0, sensors) {
+            far
         } else if self.can_move(back, sensors) {
-            back
+            (back, "turned around")
         } else {
             eyre::bail!("Completely blocked - no valid moves!");
         };
 
         self.facing = next_dir;
+        self.rationale = Some(format!("wall follower: {} (free)", turn));
         Ok(Some(next_dir))
     }
 
+    fn on_move_failed(&mut self, attempted: MoveDirection, from: UnboundedPosition) {
+        self.blocked_moves.insert((from, attempted));
+        self.facing = self.previous_facing;
+    }
+
+    fn move_rationale(&self) -> Option<String> {
+        self.rationale.clone()
+    }
+
     fn name(&self) -> &'static str {
-        "Wall Follower (Left-Hand Rule)"
+        match self.handedness {
+            Handedness::Left => "Wall Follower (Left-Hand Rule)",
+            Handedness::Right => "Wall Follower (Right-Hand Rule)",
+        }
     }
 
     fn reset(&mut self) {
@@ -147,5 +186,52 @@ impl ExplorationAlgorithm for WallFollower {
         self.visited.clear();
         self.start_pos = None;
         self.returned_to_start = false;
+        self.rationale = None;
+        self.previous_facing = MoveDirection::Up;
+        self.blocked_moves.clear();
+        self.current_pos = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// first move from the origin, then the choice at the cell above it with `sensors`
+    fn second_move(handedness: Handedness, sensors: &SensorsStates) -> MoveDirection {
+        let maze = UnboundedMaze::new();
+        let free = SensorsStates::uniform(SensorState::Free);
+        let mut follower = WallFollower::with_handedness(handedness);
+
+        let first = follower.next_move(UnboundedPosition::new(0, 0), &free, &maze);
+        assert_eq!(first.unwrap(), Some(MoveDirection::Up));
+        follower
+            .next_move(UnboundedPosition::new(-1, 0), sensors, &maze)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn each_hand_turns_toward_its_own_side() {
+        let mut sensors = SensorsStates::uniform(SensorState::Free);
+        assert_eq!(second_move(Handedness::Left, &sensors), MoveDirection::Left);
+        assert_eq!(
+            second_move(Handedness::Right, &sensors),
+            MoveDirection::Right
+        );
+
+        // with its hand side walled off it goes straight, then to the far side, then back
+        sensors.set(MoveDirection::Left, SensorState::Blocked);
+        assert_eq!(second_move(Handedness::Left, &sensors), MoveDirection::Up);
+        sensors.set(MoveDirection::Up, SensorState::Blocked);
+        assert_eq!(
+            second_move(Handedness::Left, &sensors),
+            MoveDirection::Right
+        );
+        sensors.set(MoveDirection::Right, SensorState::Blocked);
+        assert_eq!(
+            second_move(Handedness::Right, &sensors),
+            MoveDirection::Down
+        );
     }
 }
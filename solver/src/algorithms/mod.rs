@@ -1,2 +1,3 @@
 pub mod exploration;
 pub mod pathfinding;
+pub mod registry;
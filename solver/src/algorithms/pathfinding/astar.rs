@@ -1,18 +1,22 @@
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 
+use clap::ValueEnum;
+
 use crate::{
     maze::{BoundedMaze, Position},
     ros::types::MoveDirection,
 };
 
-use super::traits::PathfindingAlgorithm;
+use super::traits::{PathfindingAlgorithm, SearchOutcome};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 struct State {
     f_score: usize,
     g_score: usize,
     position: Position,
+    /// reached by continuing the parent's incoming direction; only ever set with a straight bias
+    straight: bool,
 }
 
 impl Ord for State {
@@ -20,6 +24,7 @@ impl Ord for State {
         other
             .f_score
             .cmp(&self.f_score)
+            .then_with(|| self.straight.cmp(&other.straight))
             .then_with(|| self.position.row.cmp(&other.position.row))
             .then_with(|| self.position.col.cmp(&other.position.col))
     }
@@ -31,72 +36,188 @@ impl PartialOrd for State {
     }
 }
 
-pub struct AStar;
+/// f-scores are kept as integers, scaled so fractional heuristic weights still order correctly
+const WEIGHT_SCALE: usize = 1000;
+
+/// distance estimate A* steers by; all three are admissible on the 4-connected grid, but with
+/// diagonal moves only chebyshev is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Heuristic {
+    /// row delta plus col delta, exact in open space
+    #[default]
+    Manhattan,
+    /// straight-line distance rounded to the nearest cell
+    Euclidean,
+    /// larger of the row and col deltas
+    Chebyshev,
+}
+
+impl Heuristic {
+    pub fn estimate(self, from: Position, to: Position) -> usize {
+        match self {
+            Self::Manhattan => from.manhattan_distance(to),
+            Self::Euclidean => from.euclidean_distance(to).round() as usize,
+            Self::Chebyshev => from.chebyshev_distance(to),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AStar {
+    pub heuristic: Heuristic,
+    /// among equal-cost choices prefer going straight, trading stair-step paths for ones with
+    /// fewer turns at the same length
+    pub straight_bias: bool,
+}
+
+impl AStar {
+    pub fn new(heuristic: Heuristic) -> Self {
+        Self {
+            heuristic,
+            straight_bias: false,
+        }
+    }
+
+    pub fn with_straight_bias(mut self, straight_bias: bool) -> Self {
+        self.straight_bias = straight_bias;
+        self
+    }
+}
+
+/// bounded-suboptimal A*: the heuristic is inflated by `epsilon`, so paths are at most
+/// `epsilon` times longer than optimal in exchange for fewer expansions
+pub struct WeightedAStar {
+    pub epsilon: f64,
+}
+
+impl WeightedAStar {
+    pub fn new(epsilon: f64) -> Self {
+        Self { epsilon }
+    }
+}
 
 impl PathfindingAlgorithm for AStar {
-    fn find_path(
-        &self,
-        maze: &BoundedMaze,
-        start: Position,
-        target: Position,
-    ) -> Option<Vec<MoveDirection>> {
-        let mut open_set = BinaryHeap::new();
-        let mut came_from: HashMap<Position, (Position, MoveDirection)> = HashMap::new();
-        let mut g_scores: HashMap<Position, usize> = HashMap::new();
-        let mut closed_set: HashSet<Position> = HashSet::new();
-
-        g_scores.insert(start, 0);
-        open_set.push(State {
-            f_score: start.manhattan_distance(target),
-            g_score: 0,
-            position: start,
-        });
-
-        while let Some(State {
-            position, g_score, ..
-        }) = open_set.pop()
-        {
-            if position == target {
-                return Some(reconstruct_path(&came_from, start, target));
-            }
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome {
+        weighted_search(maze, start, target, self.heuristic, 1.0, self.straight_bias)
+    }
 
-            if closed_set.contains(&position) {
-                continue;
-            }
+    fn name(&self) -> &'static str {
+        "A*"
+    }
 
-            closed_set.insert(position);
+    fn cache_key(&self) -> String {
+        let bias = if self.straight_bias { " straight" } else { "" };
+        format!("{} {:?}{}", self.name(), self.heuristic, bias)
+    }
+}
 
-            if g_score > *g_scores.get(&position).unwrap_or(&usize::MAX) {
-                continue;
-            }
+impl PathfindingAlgorithm for WeightedAStar {
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome {
+        weighted_search(
+            maze,
+            start,
+            target,
+            Heuristic::Manhattan,
+            self.epsilon,
+            false,
+        )
+    }
 
-            for (neighbor, direction) in maze.neighbors(position) {
-                if closed_set.contains(&neighbor) {
-                    continue;
-                }
+    fn name(&self) -> &'static str {
+        "Weighted A*"
+    }
+
+    fn cache_key(&self) -> String {
+        format!("{} {}", self.name(), self.epsilon)
+    }
+}
 
-                let tentative_g = g_score + 1;
-                let current_g = g_scores.get(&neighbor).copied().unwrap_or(usize::MAX);
+fn weighted_search(
+    maze: &BoundedMaze,
+    start: Position,
+    target: Position,
+    heuristic: Heuristic,
+    epsilon: f64,
+    straight_bias: bool,
+) -> SearchOutcome {
+    // every move costs at least the cheapest cell, so scaling by it keeps the estimate admissible
+    let min_cost = maze.min_cost();
+    let heuristic = |pos: Position| {
+        (heuristic.estimate(pos, target) as f64 * min_cost as f64 * epsilon * WEIGHT_SCALE as f64)
+            .round() as usize
+    };
 
-                if tentative_g < current_g {
-                    g_scores.insert(neighbor, tentative_g);
-                    came_from.insert(neighbor, (position, direction));
+    let mut open_set = BinaryHeap::new();
+    let mut came_from: HashMap<Position, (Position, MoveDirection)> = HashMap::new();
+    let mut g_scores: HashMap<Position, usize> = HashMap::new();
+    let mut closed_set: HashSet<Position> = HashSet::new();
 
-                    let f_score = tentative_g + neighbor.manhattan_distance(target);
-                    open_set.push(State {
-                        f_score,
-                        g_score: tentative_g,
-                        position: neighbor,
+    g_scores.insert(start, 0);
+    open_set.push(State {
+        f_score: heuristic(start),
+        g_score: 0,
+        position: start,
+        straight: false,
+    });
+
+    while let Some(State {
+        position, g_score, ..
+    }) = open_set.pop()
+    {
+        if position == target {
+            return SearchOutcome {
+                path: Some(reconstruct_path(&came_from, start, target)),
+                nodes_expanded: closed_set.len(),
+            };
+        }
+
+        if closed_set.contains(&position) {
+            continue;
+        }
+
+        closed_set.insert(position);
+
+        if g_score > *g_scores.get(&position).unwrap_or(&usize::MAX) {
+            continue;
+        }
+
+        let incoming = came_from.get(&position).map(|&(_, direction)| direction);
+
+        for (neighbor, direction) in maze.neighbors(position) {
+            if closed_set.contains(&neighbor) {
+                continue;
+            }
+
+            let tentative_g = g_score + maze.cost(neighbor);
+            let current_g = g_scores.get(&neighbor).copied().unwrap_or(usize::MAX);
+            let straight = straight_bias && incoming == Some(direction);
+            // an equally cheap straight parent replaces a turning one, keeping the length
+            let rebias = straight
+                && tentative_g == current_g
+                && came_from
+                    .get(&neighbor)
+                    .is_some_and(|&(parent, old_direction)| {
+                        came_from.get(&parent).map(|&(_, d)| d) != Some(old_direction)
                     });
-                }
+
+            if tentative_g < current_g || rebias {
+                g_scores.insert(neighbor, tentative_g);
+                came_from.insert(neighbor, (position, direction));
+
+                let f_score = tentative_g * WEIGHT_SCALE + heuristic(neighbor);
+                open_set.push(State {
+                    f_score,
+                    g_score: tentative_g,
+                    position: neighbor,
+                    straight,
+                });
             }
         }
-
-        None
     }
 
-    fn name(&self) -> &'static str {
-        "A*"
+    SearchOutcome {
+        path: None,
+        nodes_expanded: closed_set.len(),
     }
 }
 
@@ -120,3 +241,131 @@ fn reconstruct_path(
     path.reverse();
     path
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithms::pathfinding::{BFS, Dijkstra, path::count_turns},
+        maze::CornerPolicy,
+    };
+
+    const WALLED: &str = "\
+rffbfff
+fbffbbf
+fbbfffb
+fffbfbf
+bbffftf
+";
+
+    #[test]
+    fn manhattan_finds_the_shortest_path_on_a_4_connected_maze() {
+        let maze = BoundedMaze::from_ascii(WALLED).unwrap();
+        let start = maze.find_robot().unwrap();
+        let target = maze.find_target().unwrap();
+
+        let astar = AStar::new(Heuristic::Manhattan)
+            .find_path(&maze, start, target)
+            .unwrap();
+        let bfs = BFS.find_path(&maze, start, target).unwrap();
+
+        assert_eq!(astar.len(), bfs.len());
+    }
+
+    #[test]
+    fn chebyshev_never_overestimates_with_diagonal_moves() {
+        let maze = BoundedMaze::from_ascii(WALLED)
+            .unwrap()
+            .with_diagonal_moves(CornerPolicy::AllowBoth);
+        let target = maze.find_target().unwrap();
+
+        for row in 0..maze.height() {
+            for col in 0..maze.width() {
+                let from = Position::new(row, col);
+                if !maze.is_walkable(from) {
+                    continue;
+                }
+                let path = BFS.find_path(&maze, from, target).unwrap();
+                assert!(
+                    Heuristic::Chebyshev.estimate(from, target) <= path.len(),
+                    "estimate from {} exceeds the {} steps it takes",
+                    from,
+                    path.len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn expands_fewer_nodes_than_dijkstra_in_the_open() {
+        let mut rows = vec!["f".repeat(15); 15];
+        rows[0].replace_range(0..1, "r");
+        rows[7].replace_range(14..15, "t");
+        let maze = BoundedMaze::from_ascii(&rows.join("\n")).unwrap();
+        let start = maze.find_robot().unwrap();
+        let target = maze.find_target().unwrap();
+
+        let astar = AStar::default().search(&maze, start, target);
+        let dijkstra = Dijkstra.search(&maze, start, target);
+
+        assert_eq!(astar.path.unwrap().len(), dijkstra.path.unwrap().len());
+        assert!(
+            astar.nodes_expanded < dijkstra.nodes_expanded,
+            "a* expanded {}, dijkstra {}",
+            astar.nodes_expanded,
+            dijkstra.nodes_expanded
+        );
+    }
+
+    #[test]
+    fn diagonal_between_two_walls_is_refused() {
+        let plan = |ascii: &str, policy| {
+            let maze = BoundedMaze::from_ascii(ascii)
+                .unwrap()
+                .with_diagonal_moves(policy);
+            let start = maze.find_robot().unwrap();
+            let target = maze.find_target().unwrap();
+            AStar::new(Heuristic::Chebyshev).find_path(&maze, start, target)
+        };
+        let squeeze = "rb\nbt\n";
+        let one_wall = "rf\nbt\n";
+
+        assert_eq!(plan(squeeze, CornerPolicy::Forbid), None);
+        assert_eq!(plan(squeeze, CornerPolicy::AllowOneWall), None);
+        assert_eq!(
+            plan(squeeze, CornerPolicy::AllowBoth),
+            Some(vec![MoveDirection::DownRight])
+        );
+
+        // with one wall the forbidding policy goes around instead of cutting the corner
+        assert_eq!(
+            plan(one_wall, CornerPolicy::Forbid),
+            Some(vec![MoveDirection::Right, MoveDirection::Down])
+        );
+        assert_eq!(
+            plan(one_wall, CornerPolicy::AllowOneWall),
+            Some(vec![MoveDirection::DownRight])
+        );
+    }
+
+    #[test]
+    fn straight_bias_turns_less_at_the_same_length() {
+        let maze = BoundedMaze::from_ascii("rffff\nbffff\nfffff\nbbfbf\nfbfft\n").unwrap();
+        let start = maze.find_robot().unwrap();
+        let target = maze.find_target().unwrap();
+
+        let plain = AStar::default().find_path(&maze, start, target).unwrap();
+        let straight = AStar::default()
+            .with_straight_bias(true)
+            .find_path(&maze, start, target)
+            .unwrap();
+
+        assert_eq!(straight.len(), plain.len());
+        assert!(
+            count_turns(&straight) < count_turns(&plain),
+            "biased {:?} turns no less than {:?}",
+            straight,
+            plain
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    maze::{BoundedMaze, Position},
+    ros::types::MoveDirection,
+};
+
+use super::traits::{PathfindingAlgorithm, SearchOutcome};
+
+/// breadth-first search: shortest path on the unit-cost grid without dijkstra's heap
+#[allow(clippy::upper_case_acronyms)]
+pub struct BFS;
+
+impl PathfindingAlgorithm for BFS {
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome {
+        let mut nodes_expanded = 0;
+        let mut came_from: HashMap<Position, (Position, MoveDirection)> = HashMap::new();
+        let mut frontier = VecDeque::from([start]);
+
+        while let Some(current) = frontier.pop_front() {
+            if current == target {
+                return SearchOutcome {
+                    path: Some(reconstruct_path(&came_from, start, target)),
+                    nodes_expanded,
+                };
+            }
+            nodes_expanded += 1;
+
+            for (neighbor, direction) in maze.neighbors(current) {
+                if neighbor != start && !came_from.contains_key(&neighbor) {
+                    came_from.insert(neighbor, (current, direction));
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        SearchOutcome {
+            path: None,
+            nodes_expanded,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "BFS"
+    }
+}
+
+fn reconstruct_path(
+    came_from: &HashMap<Position, (Position, MoveDirection)>,
+    start: Position,
+    target: Position,
+) -> Vec<MoveDirection> {
+    let mut path = Vec::new();
+    let mut current = target;
+
+    while current != start {
+        if let Some(&(prev, direction)) = came_from.get(&current) {
+            path.push(direction);
+            current = prev;
+        } else {
+            break;
+        }
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::pathfinding::path_to_waypoints;
+
+    #[test]
+    fn finds_the_shorter_of_two_ways_round() {
+        // straight down takes 2 moves, round the wall on the right 8
+        let maze = BoundedMaze::from_ascii("rfff\nfbbf\ntfff\n").unwrap();
+        let start = maze.find_robot().unwrap();
+        let target = maze.find_target().unwrap();
+
+        let path = BFS.find_path(&maze, start, target).unwrap();
+
+        assert_eq!(path, [MoveDirection::Down, MoveDirection::Down]);
+        assert_eq!(path_to_waypoints(start, &path).last(), Some(&target));
+    }
+
+    #[test]
+    fn walled_off_target_has_no_path() {
+        let maze = BoundedMaze::from_ascii("rfbff\nffbft\n").unwrap();
+        let start = maze.find_robot().unwrap();
+        let target = maze.find_target().unwrap();
+
+        let outcome = BFS.search(&maze, start, target);
+
+        assert_eq!(outcome.path, None);
+        // every cell on the robot's side of the wall was expanded before giving up
+        assert_eq!(outcome.nodes_expanded, 4);
+    }
+}
@@ -1,22 +1,61 @@
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 
+use clap::ValueEnum;
+
 use crate::{
     maze::{BoundedMaze, Position},
     ros::types::MoveDirection,
 };
 
-use super::traits::PathfindingAlgorithm;
+use super::traits::{PathfindingAlgorithm, SearchOutcome};
 
+/// depth-first search: returns the first path it stumbles onto, which is usually far from the
+/// shortest one; see `IDDFS` for a depth-first search that is optimal
 #[allow(clippy::upper_case_acronyms)]
-pub struct DFS;
+#[derive(Default)]
+pub struct DFS {
+    pub order: NeighborOrder,
+}
 
-impl PathfindingAlgorithm for DFS {
-    fn find_path(
+/// which of a cell's neighbors `DFS` expands first, which decides the route it stumbles onto
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum NeighborOrder {
+    /// the reverse of the order `BoundedMaze::neighbors` lists them in, since the last one
+    /// pushed is popped first
+    #[default]
+    Default,
+    /// the order `BoundedMaze::neighbors` lists them in
+    Reversed,
+    /// the one closest to the target by manhattan distance
+    TowardTarget,
+}
+
+impl DFS {
+    pub fn new(order: NeighborOrder) -> Self {
+        Self { order }
+    }
+
+    /// `neighbors` rearranged so the one to expand first is pushed last
+    fn push_order(
         &self,
-        maze: &BoundedMaze,
-        start: Position,
+        mut neighbors: Vec<(Position, MoveDirection)>,
         target: Position,
-    ) -> Option<Vec<MoveDirection>> {
+    ) -> Vec<(Position, MoveDirection)> {
+        match self.order {
+            NeighborOrder::Default => {}
+            NeighborOrder::Reversed => neighbors.reverse(),
+            NeighborOrder::TowardTarget => {
+                neighbors.sort_by_key(|(pos, _)| Reverse(pos.manhattan_distance(target)))
+            }
+        }
+        neighbors
+    }
+}
+
+impl PathfindingAlgorithm for DFS {
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome {
+        let mut nodes_expanded = 0;
         let mut visited = HashSet::new();
         let mut came_from = HashMap::new();
         let mut stack = vec![start];
@@ -24,10 +63,14 @@ impl PathfindingAlgorithm for DFS {
 
         while let Some(current) = stack.pop() {
             if current == target {
-                return Some(reconstruct_path(&came_from, start, target));
+                return SearchOutcome {
+                    path: Some(reconstruct_path(&came_from, start, target)),
+                    nodes_expanded,
+                };
             }
+            nodes_expanded += 1;
 
-            for (neighbor, direction) in maze.neighbors(current) {
+            for (neighbor, direction) in self.push_order(maze.neighbors(current), target) {
                 if !visited.contains(&neighbor) {
                     visited.insert(neighbor);
                     came_from.insert(neighbor, (current, direction));
@@ -36,12 +79,19 @@ impl PathfindingAlgorithm for DFS {
             }
         }
 
-        None
+        SearchOutcome {
+            path: None,
+            nodes_expanded,
+        }
     }
 
     fn name(&self) -> &'static str {
         "DFS"
     }
+
+    fn cache_key(&self) -> String {
+        format!("{} {:?}", self.name(), self.order)
+    }
 }
 
 fn reconstruct_path(
@@ -64,3 +114,19 @@ fn reconstruct_path(
     path.reverse();
     path
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MoveDirection::{Down, Right};
+
+    #[test]
+    fn neighbor_order_changes_the_route() {
+        let maze = BoundedMaze::from_ascii("rff\nfbf\nfft\n").unwrap();
+        let (start, target) = (maze.find_robot().unwrap(), maze.find_target().unwrap());
+        let route = |order| DFS::new(order).find_path(&maze, start, target).unwrap();
+
+        assert_eq!(route(NeighborOrder::Default), [Right, Right, Down, Down]);
+        assert_eq!(route(NeighborOrder::Reversed), [Down, Down, Right, Right]);
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
     ros::types::MoveDirection,
 };
 
-use super::traits::PathfindingAlgorithm;
+use super::traits::{PathfindingAlgorithm, SearchOutcome};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 struct State {
@@ -32,13 +32,14 @@ impl PartialOrd for State {
 
 pub struct Dijkstra;
 
-impl PathfindingAlgorithm for Dijkstra {
-    fn find_path(
+impl Dijkstra {
+    /// single uniform-cost search that stops at whichever target is popped first
+    pub fn find_nearest(
         &self,
         maze: &BoundedMaze,
         start: Position,
-        target: Position,
-    ) -> Option<Vec<MoveDirection>> {
+        targets: &[Position],
+    ) -> Option<(Position, Vec<MoveDirection>)> {
         let mut heap = BinaryHeap::new();
         let mut distances: HashMap<Position, usize> = HashMap::new();
         let mut came_from: HashMap<Position, (Position, MoveDirection)> = HashMap::new();
@@ -50,8 +51,8 @@ impl PathfindingAlgorithm for Dijkstra {
         distances.insert(start, 0);
 
         while let Some(State { cost, position }) = heap.pop() {
-            if position == target {
-                return Some(reconstruct_path(&came_from, start, target));
+            if targets.contains(&position) {
+                return Some((position, reconstruct_path(&came_from, start, position)));
             }
 
             if cost > *distances.get(&position).unwrap_or(&usize::MAX) {
@@ -59,7 +60,7 @@ impl PathfindingAlgorithm for Dijkstra {
             }
 
             for (neighbor, direction) in maze.neighbors(position) {
-                let new_cost = cost + 1;
+                let new_cost = cost + maze.cost(neighbor);
                 let current_dist = distances.get(&neighbor).copied().unwrap_or(usize::MAX);
 
                 if new_cost < current_dist {
@@ -75,6 +76,54 @@ impl PathfindingAlgorithm for Dijkstra {
 
         None
     }
+}
+
+impl PathfindingAlgorithm for Dijkstra {
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome {
+        let mut nodes_expanded = 0;
+        let mut heap = BinaryHeap::new();
+        let mut distances: HashMap<Position, usize> = HashMap::new();
+        let mut came_from: HashMap<Position, (Position, MoveDirection)> = HashMap::new();
+
+        heap.push(State {
+            cost: 0,
+            position: start,
+        });
+        distances.insert(start, 0);
+
+        while let Some(State { cost, position }) = heap.pop() {
+            if position == target {
+                return SearchOutcome {
+                    path: Some(reconstruct_path(&came_from, start, target)),
+                    nodes_expanded,
+                };
+            }
+
+            if cost > *distances.get(&position).unwrap_or(&usize::MAX) {
+                continue;
+            }
+            nodes_expanded += 1;
+
+            for (neighbor, direction) in maze.neighbors(position) {
+                let new_cost = cost + maze.cost(neighbor);
+                let current_dist = distances.get(&neighbor).copied().unwrap_or(usize::MAX);
+
+                if new_cost < current_dist {
+                    distances.insert(neighbor, new_cost);
+                    came_from.insert(neighbor, (position, direction));
+                    heap.push(State {
+                        cost: new_cost,
+                        position: neighbor,
+                    });
+                }
+            }
+        }
+
+        SearchOutcome {
+            path: None,
+            nodes_expanded,
+        }
+    }
 
     fn name(&self) -> &'static str {
         "Dijkstra"
@@ -101,3 +150,80 @@ fn reconstruct_path(
     path.reverse();
     path
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::pathfinding::{AStar, BFS};
+
+    /// unit-cost mazes where several shortest paths tie, with their shortest length
+    const TIED_MAZES: [(&str, usize); 3] = [
+        ("rfff\nffff\nffff\nffft\n", 6),
+        ("bbbbbbb\nbrfffbb\nbfbfbfb\nbfffftb\nbbbbbbb\n", 6),
+        ("rbt\nfbf\nfff\n", 6),
+    ];
+
+    #[test]
+    fn bfs_and_dijkstra_agree_on_tied_paths() {
+        for (ascii, shortest) in TIED_MAZES {
+            let maze = BoundedMaze::from_ascii(ascii).unwrap();
+            let start = maze.find_robot().unwrap();
+            let target = maze.find_target().unwrap();
+
+            let bfs = BFS.find_path(&maze, start, target).unwrap();
+            let dijkstra = Dijkstra.find_path(&maze, start, target).unwrap();
+
+            assert!(
+                bfs.len() == shortest && dijkstra.len() == shortest,
+                "expected {} steps on\n{}bfs: {:?}\ndijkstra: {:?}",
+                shortest,
+                ascii,
+                bfs,
+                dijkstra
+            );
+        }
+    }
+
+    #[test]
+    fn cost_aware_searches_detour_around_slow_cells() {
+        // straight through costs 3 + 3 + 1, the way round below five steps of 1
+        let maze = BoundedMaze::from_ascii("rsst\nffff\n").unwrap();
+        let start = maze.find_robot().unwrap();
+        let target = maze.find_target().unwrap();
+        let detour = [
+            MoveDirection::Down,
+            MoveDirection::Right,
+            MoveDirection::Right,
+            MoveDirection::Right,
+            MoveDirection::Up,
+        ];
+
+        assert_eq!(BFS.find_path(&maze, start, target).unwrap().len(), 3);
+        assert_eq!(Dijkstra.find_path(&maze, start, target).unwrap(), detour);
+        assert_eq!(
+            AStar::default().find_path(&maze, start, target).unwrap(),
+            detour
+        );
+    }
+
+    #[test]
+    fn find_nearest_stops_at_the_cheapest_target() {
+        // the target to the right is the fewest steps away, but through a slow cell
+        let maze = BoundedMaze::from_ascii("tfffrst\nbbbbfbb\nbbbbfbb\nbbbbtbb\n").unwrap();
+        let start = maze.find_robot().unwrap();
+        let targets = [
+            Position::new(0, 0),
+            Position::new(0, 6),
+            Position::new(3, 4),
+        ];
+
+        let (nearest, path) = Dijkstra.find_nearest(&maze, start, &targets).unwrap();
+
+        assert_eq!(nearest, Position::new(3, 4));
+        assert_eq!(path, [MoveDirection::Down; 3]);
+        assert_eq!(
+            Dijkstra.find_nearest(&maze, start, &[Position::new(1, 0)]),
+            None
+        );
+    }
+}
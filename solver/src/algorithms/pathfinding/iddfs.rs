@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use crate::{
+    maze::{BoundedMaze, Position},
+    ros::types::MoveDirection,
+};
+
+use super::traits::{PathfindingAlgorithm, SearchOutcome};
+
+/// iterative-deepening depth-first search: depth-limited dfs with a bound raised by one until
+/// the target turns up, so the first path found is a shortest one
+///
+/// each pass keeps the shallowest depth every cell was reached at, so a pass never re-expands a
+/// cell it already reached as cheaply. that table makes memory O(V) like bfs rather than the
+/// O(depth) of plain dfs; pruning only along the current branch would be exponential in time
+#[allow(clippy::upper_case_acronyms)]
+pub struct IDDFS;
+
+enum DepthLimited {
+    Found(Vec<MoveDirection>),
+    /// some branch hit the bound, a deeper pass may still find the target
+    CutOff,
+    /// every reachable cell was expanded without reaching the target
+    Exhausted,
+}
+
+impl IDDFS {
+    fn depth_limited(
+        maze: &BoundedMaze,
+        start: Position,
+        target: Position,
+        limit: usize,
+        nodes_expanded: &mut usize,
+    ) -> DepthLimited {
+        let mut best_depth = HashMap::from([(start, 0)]);
+        let mut path = Vec::new();
+        let mut cut_off = false;
+
+        *nodes_expanded += 1;
+        let mut stack = vec![maze.neighbors(start).into_iter()];
+
+        while let Some(neighbors) = stack.last_mut() {
+            let Some((neighbor, direction)) = neighbors.next() else {
+                stack.pop();
+                path.pop();
+                continue;
+            };
+
+            let depth = path.len() + 1;
+            if best_depth.get(&neighbor).is_some_and(|&best| best <= depth) {
+                continue;
+            }
+            best_depth.insert(neighbor, depth);
+
+            if neighbor == target {
+                path.push(direction);
+                return DepthLimited::Found(path);
+            }
+            if depth == limit {
+                cut_off = true;
+                continue;
+            }
+
+            *nodes_expanded += 1;
+            path.push(direction);
+            stack.push(maze.neighbors(neighbor).into_iter());
+        }
+
+        if cut_off {
+            DepthLimited::CutOff
+        } else {
+            DepthLimited::Exhausted
+        }
+    }
+}
+
+impl PathfindingAlgorithm for IDDFS {
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome {
+        let mut nodes_expanded = 0;
+        if start == target {
+            return SearchOutcome {
+                path: Some(Vec::new()),
+                nodes_expanded,
+            };
+        }
+
+        for limit in 1.. {
+            match Self::depth_limited(maze, start, target, limit, &mut nodes_expanded) {
+                DepthLimited::Found(path) => {
+                    return SearchOutcome {
+                        path: Some(path),
+                        nodes_expanded,
+                    };
+                }
+                DepthLimited::CutOff => {}
+                DepthLimited::Exhausted => break,
+            }
+        }
+
+        SearchOutcome {
+            path: None,
+            nodes_expanded,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "IDDFS"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::pathfinding::BFS;
+
+    #[test]
+    fn iddfs_matches_bfs_path_length() {
+        let mazes = [
+            "rfff\nffff\nffff\nffft\n",
+            "bbbbbbb\nbrffffb\nbfbbbfb\nbfffbtb\nbbbbbbb\n",
+            "rbfff\nfbfbf\nfffbt\n",
+            "rfffffff\nbbbbbbbf\ntfffffff\n",
+            "rfb\nbbb\nbft\n",
+        ];
+
+        for ascii in mazes {
+            let maze = BoundedMaze::from_ascii(ascii).unwrap();
+            let start = maze.find_robot().unwrap();
+            let target = maze.find_target().unwrap();
+
+            let iddfs = IDDFS.find_path(&maze, start, target);
+            let bfs = BFS.find_path(&maze, start, target);
+
+            assert_eq!(
+                iddfs.as_ref().map(Vec::len),
+                bfs.as_ref().map(Vec::len),
+                "on\n{}iddfs: {:?}\nbfs: {:?}",
+                ascii,
+                iddfs,
+                bfs
+            );
+        }
+    }
+}
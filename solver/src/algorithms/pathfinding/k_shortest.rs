@@ -0,0 +1,176 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::{
+    maze::{BoundedMaze, Position},
+    ros::types::MoveDirection,
+};
+
+/// one candidate route: every cell it visits, `start` included, and the moves between them
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Route {
+    cost: usize,
+    cells: Vec<Position>,
+    moves: Vec<MoveDirection>,
+}
+
+/// up to `k` distinct loopless routes from `start` to `target`, cheapest first, using yen's
+/// algorithm over dijkstra; cost is the same per-cell cost dijkstra and a* minimize
+///
+/// fewer than `k` routes come back when the maze has no more
+pub fn k_shortest_paths(
+    maze: &BoundedMaze,
+    start: Position,
+    target: Position,
+    k: usize,
+) -> Vec<Vec<MoveDirection>> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let Some(first) = constrained_dijkstra(maze, start, target, &HashSet::new(), &HashSet::new())
+    else {
+        return Vec::new();
+    };
+
+    let mut found: Vec<Route> = vec![first];
+    let mut candidates: Vec<Route> = Vec::new();
+
+    while found.len() < k {
+        let previous = found.last().expect("found starts with one route").clone();
+
+        for spur_idx in 0..previous.moves.len() {
+            let spur = previous.cells[spur_idx];
+            let root_cells = &previous.cells[..=spur_idx];
+
+            // leave every known route sharing this root by a different edge than before
+            let removed_edges: HashSet<(Position, Position)> = found
+                .iter()
+                .filter(|route| route.cells.starts_with(root_cells))
+                .filter_map(|route| Some((spur, *route.cells.get(spur_idx + 1)?)))
+                .collect();
+            // and never revisit the root, which keeps the spliced route loopless
+            let removed_cells: HashSet<Position> = root_cells[..spur_idx].iter().copied().collect();
+
+            let Some(spur_route) =
+                constrained_dijkstra(maze, spur, target, &removed_cells, &removed_edges)
+            else {
+                continue;
+            };
+
+            let root_cost: usize = root_cells[1..].iter().map(|&cell| maze.cost(cell)).sum();
+            let mut cells = root_cells.to_vec();
+            cells.extend_from_slice(&spur_route.cells[1..]);
+            let mut moves = previous.moves[..spur_idx].to_vec();
+            moves.extend(spur_route.moves);
+
+            let candidate = Route {
+                cost: root_cost + spur_route.cost,
+                cells,
+                moves,
+            };
+            if !found.contains(&candidate) && !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+
+        // cheapest first, fewer moves breaking ties, then whichever was found first
+        let Some(best) = candidates
+            .iter()
+            .enumerate()
+            .min_by_key(|(idx, route)| (route.cost, route.moves.len(), *idx))
+            .map(|(idx, _)| idx)
+        else {
+            break;
+        };
+        found.push(candidates.remove(best));
+    }
+
+    found.into_iter().map(|route| route.moves).collect()
+}
+
+/// dijkstra that never enters `removed_cells` and never takes a `removed_edges` step
+fn constrained_dijkstra(
+    maze: &BoundedMaze,
+    start: Position,
+    target: Position,
+    removed_cells: &HashSet<Position>,
+    removed_edges: &HashSet<(Position, Position)>,
+) -> Option<Route> {
+    let mut heap = BinaryHeap::from([Reverse((0, start.row, start.col))]);
+    let mut distances: HashMap<Position, usize> = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<Position, (Position, MoveDirection)> = HashMap::new();
+
+    while let Some(Reverse((cost, row, col))) = heap.pop() {
+        let position = Position::new(row, col);
+        if position == target {
+            return Some(route_to(&came_from, start, target, cost));
+        }
+        if cost > distances.get(&position).copied().unwrap_or(usize::MAX) {
+            continue;
+        }
+
+        for (neighbor, direction) in maze.neighbors(position) {
+            if removed_cells.contains(&neighbor) || removed_edges.contains(&(position, neighbor)) {
+                continue;
+            }
+
+            let new_cost = cost + maze.cost(neighbor);
+            if new_cost < distances.get(&neighbor).copied().unwrap_or(usize::MAX) {
+                distances.insert(neighbor, new_cost);
+                came_from.insert(neighbor, (position, direction));
+                heap.push(Reverse((new_cost, neighbor.row, neighbor.col)));
+            }
+        }
+    }
+
+    None
+}
+
+fn route_to(
+    came_from: &HashMap<Position, (Position, MoveDirection)>,
+    start: Position,
+    target: Position,
+    cost: usize,
+) -> Route {
+    let mut cells = vec![target];
+    let mut moves = Vec::new();
+    let mut current = target;
+
+    while current != start {
+        let Some(&(prev, direction)) = came_from.get(&current) else {
+            break;
+        };
+        cells.push(prev);
+        moves.push(direction);
+        current = prev;
+    }
+
+    cells.reverse();
+    moves.reverse();
+    Route { cost, cells, moves }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::pathfinding::path_to_waypoints;
+
+    #[test]
+    fn both_sides_of_a_ring_come_back_once_each() {
+        let maze = BoundedMaze::from_ascii("bbbbb\nbrffb\nbfbfb\nbfftb\nbbbbb\n").unwrap();
+        let start = maze.find_robot().unwrap();
+        let target = maze.find_target().unwrap();
+
+        // only two loopless routes exist, so asking for more returns just those
+        let routes = k_shortest_paths(&maze, start, target, 3);
+
+        assert_eq!(routes.len(), 2);
+        assert_ne!(routes[0], routes[1]);
+        assert_eq!(routes[0].len(), 4);
+        assert_eq!(routes[1].len(), 4);
+        for route in &routes {
+            assert_eq!(path_to_waypoints(start, route).last(), Some(&target));
+        }
+    }
+}
@@ -1,9 +1,18 @@
 mod astar;
+mod bfs;
 mod dfs;
 mod dijkstra;
+mod iddfs;
+mod k_shortest;
+pub mod path;
 pub mod traits;
+mod waypoints;
 
-pub use astar::AStar;
-pub use dfs::DFS;
+pub use astar::{AStar, Heuristic, WeightedAStar};
+pub use bfs::BFS;
+pub use dfs::{DFS, NeighborOrder};
 pub use dijkstra::Dijkstra;
+pub use iddfs::IDDFS;
+pub use k_shortest::k_shortest_paths;
 pub use traits::{PathResult, PathfindingAlgorithm};
+pub use waypoints::{path_to_waypoints, write_waypoints_csv};
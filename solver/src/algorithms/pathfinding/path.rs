@@ -0,0 +1,205 @@
+use std::cmp::Ordering;
+use std::iter::repeat_n;
+
+use crate::{
+    maze::{BoundedMaze, Position},
+    ros::types::MoveDirection,
+};
+
+/// drops every move that is immediately undone by its opposite, repeatedly, so `up, left,
+/// right, down` collapses to nothing
+///
+/// the remaining moves visit a subset of the original cells in the same order, so the result
+/// is valid wherever the input was
+pub fn simplify(path: &[MoveDirection]) -> Vec<MoveDirection> {
+    let mut simplified: Vec<MoveDirection> = Vec::with_capacity(path.len());
+    for &direction in path {
+        if simplified.last() == Some(&direction.opposite()) {
+            simplified.pop();
+        } else {
+            simplified.push(direction);
+        }
+    }
+    simplified
+}
+
+/// `simplify`, then greedily replaces each stretch of the path with a straight line or a
+/// single-turn route to the furthest later cell it can reach that way through walkable cells
+///
+/// a route never costs more than the stretch it replaces, counting slow cells, so the result
+/// is at most as expensive and usually has far fewer turns. paths with diagonal moves are only
+/// simplified
+pub fn straighten(
+    maze: &BoundedMaze,
+    start: Position,
+    path: &[MoveDirection],
+) -> Vec<MoveDirection> {
+    let path = simplify(path);
+    if path.iter().any(|direction| direction.is_diagonal()) {
+        return path;
+    }
+
+    let Some(positions) = positions(maze, start, &path) else {
+        return path;
+    };
+    // cost_to[k] is what reaching positions[k] from the start costs along the original path
+    let cost_to: Vec<usize> = std::iter::once(0)
+        .chain(positions[1..].iter().scan(0, |cost, &pos| {
+            *cost += maze.cost(pos);
+            Some(*cost)
+        }))
+        .collect();
+
+    let mut straightened = Vec::with_capacity(path.len());
+    let mut i = 0;
+    while i + 1 < positions.len() {
+        let heading = straightened.last().copied();
+        let (j, route) = (i + 1..positions.len())
+            .rev()
+            .find_map(|j| {
+                let budget = cost_to[j] - cost_to[i];
+                single_turn_route(maze, positions[i], positions[j], heading, budget)
+                    .map(|route| (j, route))
+            })
+            .unwrap_or_else(|| (i + 1, vec![path[i]]));
+
+        straightened.extend(route);
+        i = j;
+    }
+    straightened
+}
+
+/// how many times consecutive moves change direction
+pub fn count_turns(path: &[MoveDirection]) -> usize {
+    path.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+/// every cell `path` passes through, `start` included, or `None` if it leaves the maze
+fn positions(maze: &BoundedMaze, start: Position, path: &[MoveDirection]) -> Option<Vec<Position>> {
+    let mut positions = vec![start];
+    for &direction in path {
+        let next = positions
+            .last()?
+            .move_in_direction(direction, maze.bounds())?;
+        positions.push(next);
+    }
+    Some(positions)
+}
+
+/// a straight or L-shaped route from `from` to `to` over walkable cells costing at most
+/// `budget`, trying the leg that keeps `heading` first
+fn single_turn_route(
+    maze: &BoundedMaze,
+    from: Position,
+    to: Position,
+    heading: Option<MoveDirection>,
+    budget: usize,
+) -> Option<Vec<MoveDirection>> {
+    let vertical = match to.row.cmp(&from.row) {
+        Ordering::Less => MoveDirection::Up,
+        _ => MoveDirection::Down,
+    };
+    let horizontal = match to.col.cmp(&from.col) {
+        Ordering::Less => MoveDirection::Left,
+        _ => MoveDirection::Right,
+    };
+    let rows = from.row.abs_diff(to.row);
+    let cols = from.col.abs_diff(to.col);
+
+    let vertical_first: Vec<_> = repeat_n(vertical, rows)
+        .chain(repeat_n(horizontal, cols))
+        .collect();
+    let horizontal_first: Vec<_> = repeat_n(horizontal, cols)
+        .chain(repeat_n(vertical, rows))
+        .collect();
+
+    let candidates = if heading == Some(horizontal) {
+        [horizontal_first, vertical_first]
+    } else {
+        [vertical_first, horizontal_first]
+    };
+    candidates
+        .into_iter()
+        .find(|route| route_cost(maze, from, route).is_some_and(|cost| cost <= budget))
+}
+
+/// what walking `route` from `from` costs, `None` if it leaves the walkable cells
+fn route_cost(maze: &BoundedMaze, from: Position, route: &[MoveDirection]) -> Option<usize> {
+    let mut current = from;
+    route.iter().try_fold(0, |cost, &direction| {
+        current = current
+            .move_in_direction(direction, maze.bounds())
+            .filter(|&next| maze.is_walkable(next))?;
+        Some(cost + maze.cost(current))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::algorithms::pathfinding::{AStar, BFS, Dijkstra, PathResult, PathfindingAlgorithm};
+    use MoveDirection::{Down, Left, Right, Up};
+
+    #[test]
+    fn start_on_the_target_is_an_empty_path_with_one_waypoint() {
+        let maze = BoundedMaze::from_ascii("rf\nft\n").unwrap();
+        let at = maze.find_target().unwrap();
+
+        for algorithm in [
+            &BFS as &dyn PathfindingAlgorithm,
+            &Dijkstra,
+            &AStar::default(),
+        ] {
+            assert_eq!(algorithm.find_path(&maze, at, at), Some(vec![]));
+        }
+        assert!(straighten(&maze, at, &[]).is_empty());
+        assert_eq!(count_turns(&[]), 0);
+        assert_eq!(positions(&maze, at, &[]), Some(vec![at]));
+
+        let result = PathResult::new(0, Duration::ZERO, Duration::ZERO).with_path(&maze, at, &[]);
+        assert_eq!(result.steps, 0);
+        assert!(result.path.is_empty());
+        assert_eq!(result.waypoints, [at]);
+        assert_eq!(result.direction_counts, [0; 4]);
+    }
+
+    #[test]
+    fn simplify_cancels_opposite_moves() {
+        assert_eq!(simplify(&[Up, Left, Right, Down, Right]), [Right]);
+        assert_eq!(simplify(&[Up, Right, Down]), [Up, Right, Down]);
+    }
+
+    #[test]
+    fn straighten_removes_zig_zags_over_free_cells() {
+        let maze = BoundedMaze::from_ascii("rff\nfff\nfft\n").unwrap();
+        let start = maze.find_robot().unwrap();
+
+        let straightened = straighten(&maze, start, &[Right, Down, Right, Down]);
+
+        assert_eq!(straightened, [Down, Down, Right, Right]);
+        assert_eq!(
+            route_cost(&maze, start, &straightened),
+            route_cost(&maze, start, &[Right, Down, Right, Down])
+        );
+    }
+
+    #[test]
+    fn straighten_keeps_a_detour_around_a_slow_corridor() {
+        let path = [Down, Right, Right, Right, Right, Up];
+
+        // over free cells the straight line is the same price and wins
+        let free = BoundedMaze::from_ascii("rffft\nfffff\n").unwrap();
+        assert_eq!(straighten(&free, Position::new(0, 0), &path), [Right; 4]);
+
+        // through the slow corridor it would cost 10 against the detour's 6
+        let slow = BoundedMaze::from_ascii("rssst\nfffff\n").unwrap();
+        let straightened = straighten(&slow, Position::new(0, 0), &path);
+        assert_eq!(straightened, path);
+        assert_eq!(
+            route_cost(&slow, Position::new(0, 0), &straightened),
+            Some(6)
+        );
+    }
+}
@@ -5,16 +5,58 @@ use crate::{
     ros::types::MoveDirection,
 };
 
+use super::waypoints::path_to_waypoints;
+
+/// what one search produced, with how much work it took
+pub struct SearchOutcome {
+    pub path: Option<Vec<MoveDirection>>,
+    /// nodes taken off the frontier and expanded, stale duplicates not counted
+    pub nodes_expanded: usize,
+}
+
 pub trait PathfindingAlgorithm {
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome;
+
     fn find_path(
         &self,
         maze: &BoundedMaze,
         start: Position,
         target: Position,
-    ) -> Option<Vec<MoveDirection>>;
+    ) -> Option<Vec<MoveDirection>> {
+        self.search(maze, start, target).path
+    }
 
     #[allow(dead_code)]
     fn name(&self) -> &'static str;
+
+    /// `name` plus whichever settings change the paths planned, so cached paths are only
+    /// reused by the exact variant that planned them
+    fn cache_key(&self) -> String {
+        self.name().to_string()
+    }
+}
+
+impl<T: PathfindingAlgorithm + ?Sized> PathfindingAlgorithm for Box<T> {
+    fn search(&self, maze: &BoundedMaze, start: Position, target: Position) -> SearchOutcome {
+        (**self).search(maze, start, target)
+    }
+
+    fn find_path(
+        &self,
+        maze: &BoundedMaze,
+        start: Position,
+        target: Position,
+    ) -> Option<Vec<MoveDirection>> {
+        (**self).find_path(maze, start, target)
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn cache_key(&self) -> String {
+        (**self).cache_key()
+    }
 }
 
 pub struct PathResult {
@@ -22,6 +64,19 @@ pub struct PathResult {
     pub planning_time: Duration,
     pub execution_time: Duration,
     pub total_time: Duration,
+    /// absolute positions along the executed path, in the planning maze's coordinates
+    pub waypoints: Vec<Position>,
+    /// number of up, down, left and right moves in the planned path, in `MoveDirection::all` order;
+    /// diagonal moves are not counted
+    pub direction_counts: [usize; 4],
+    /// planned moves, in execution order
+    pub path: Vec<MoveDirection>,
+    /// (height, width) of the maze the waypoints refer to
+    pub maze_dims: (usize, usize),
+    /// the maze the path was planned on, as it was when planning finished
+    pub maze: Option<BoundedMaze>,
+    /// nodes the pathfinder expanded planning the path, 0 when nothing was searched
+    pub nodes_expanded: usize,
 }
 
 impl PathResult {
@@ -32,6 +87,43 @@ impl PathResult {
             planning_time,
             execution_time,
             total_time,
+            waypoints: Vec::new(),
+            direction_counts: [0; 4],
+            path: Vec::new(),
+            maze_dims: (0, 0),
+            maze: None,
+            nodes_expanded: 0,
+        }
+    }
+
+    /// records how many nodes the search behind this result expanded
+    pub fn with_nodes(mut self, nodes_expanded: usize) -> Self {
+        self.nodes_expanded = nodes_expanded;
+        self
+    }
+
+    /// records the planned path, as absolute waypoints from `start` and per-direction move counts
+    pub fn with_path(
+        mut self,
+        maze: &BoundedMaze,
+        start: Position,
+        path: &[MoveDirection],
+    ) -> Self {
+        self.waypoints = path_to_waypoints(start, path);
+        self.direction_counts = direction_counts(path);
+        self.path = path.to_vec();
+        self.maze_dims = (maze.height(), maze.width());
+        self.maze = Some(maze.clone());
+        self
+    }
+}
+
+pub fn direction_counts(path: &[MoveDirection]) -> [usize; 4] {
+    let mut counts = [0; 4];
+    for direction in path {
+        if let Some(idx) = MoveDirection::all().iter().position(|d| d == direction) {
+            counts[idx] += 1;
         }
     }
+    counts
 }
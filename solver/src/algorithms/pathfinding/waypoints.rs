@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use crate::{maze::Position, ros::types::MoveDirection};
+
+/// absolute `(row, col)` positions visited by following `path` from `start`, including `start`
+pub fn path_to_waypoints(start: Position, path: &[MoveDirection]) -> Vec<Position> {
+    let mut waypoints = Vec::with_capacity(path.len() + 1);
+    let mut current = start;
+    waypoints.push(current);
+
+    for direction in path {
+        match current + direction.offset() {
+            Some(next) => current = next,
+            None => break,
+        }
+        waypoints.push(current);
+    }
+
+    waypoints
+}
+
+pub fn write_waypoints_csv(path: &Path, waypoints: &[Position]) -> eyre::Result<()> {
+    let mut csv = String::from("step,row,col\n");
+    for (step, waypoint) in waypoints.iter().enumerate() {
+        csv.push_str(&format!("{},{},{}\n", step, waypoint.row, waypoint.col));
+    }
+
+    std::fs::write(path, csv)?;
+    Ok(())
+}
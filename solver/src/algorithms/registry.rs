@@ -0,0 +1,53 @@
+use super::{
+    exploration::{self, ExplorationAlgorithm},
+    pathfinding::{self, PathfindingAlgorithm},
+};
+use crate::ros::types::MoveDirection;
+
+/// construction knobs for explorers, each explorer reads only the ones it cares about
+#[derive(Debug, Clone, Copy)]
+pub struct ExplorerOptions {
+    /// moves the composite explorer hands to its fallback after detecting a loop
+    pub fallback_moves: usize,
+    /// order the recursive backtracker tries unvisited neighbors in
+    pub priority: [MoveDirection; 4],
+}
+
+/// looks up a pathfinder by its cli value name, the one place pathfinders are instantiated
+pub fn pathfinder_by_name(name: &str) -> Option<Box<dyn PathfindingAlgorithm>> {
+    match name.to_lowercase().as_str() {
+        "astar" | "a-star" => Some(Box::new(pathfinding::AStar::default())),
+        "dijkstra" => Some(Box::new(pathfinding::Dijkstra)),
+        "dfs" => Some(Box::new(pathfinding::DFS::default())),
+        "bfs" => Some(Box::new(pathfinding::BFS)),
+        "iddfs" => Some(Box::new(pathfinding::IDDFS)),
+        _ => None,
+    }
+}
+
+/// looks up an explorer by its cli value name
+pub fn explorer_by_name(
+    name: &str,
+    options: ExplorerOptions,
+) -> Option<Box<dyn ExplorationAlgorithm>> {
+    let backtracker = || exploration::RecursiveBacktracker::with_priority(options.priority);
+
+    match name.to_lowercase().as_str() {
+        "wall-follower" => Some(Box::new(exploration::WallFollower::new())),
+        "wall-follower-right" => Some(Box::new(exploration::WallFollower::with_handedness(
+            exploration::Handedness::Right,
+        ))),
+        "recursive-backtracker" => Some(Box::new(backtracker())),
+        "composite" => Some(Box::new(exploration::Composite::new(
+            Box::new(exploration::WallFollower::new()),
+            Box::new(backtracker()),
+            options.fallback_moves,
+        ))),
+        "spanning-tree" => Some(Box::new(exploration::SpanningTreeCoverage::new())),
+        "spiral" => Some(Box::new(exploration::SpiralExplorer::new())),
+        "pledge" => Some(Box::new(exploration::Pledge::new())),
+        "frontier" => Some(Box::new(exploration::FrontierExploration::new())),
+        "tremaux" => Some(Box::new(exploration::Tremaux::new())),
+        _ => None,
+    }
+}
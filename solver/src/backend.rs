@@ -0,0 +1,215 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, watch};
+
+use crate::ros::types::{MoveDirection, SensorsStates};
+
+const MAP_RETRY_INTERVAL: Duration = Duration::from_millis(200);
+/// pause before retrying a failed move, doubled after every further failure
+pub const MOVE_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// occupancy grid as the simulator reports it: one symbol per cell, row-major, plus its shape
+#[derive(Debug, Clone, Default)]
+pub struct RawMap {
+    pub occupancy_grid_flattened: Vec<String>,
+    pub occupancy_grid_shape: Vec<u8>,
+}
+
+/// one sensor reading, numbered in the order the backend received it
+#[derive(Debug, Clone)]
+pub struct SensorFrame {
+    /// 1 for the first frame and one more for every later one, so a consumer can tell a new
+    /// frame from one it already saw
+    pub seq: u64,
+    pub received_at: Instant,
+    pub states: SensorsStates,
+}
+
+/// the maze a solver drives
+///
+/// `ROSInterface` talks to the cg simulator; embedders can implement this over their own
+/// simulator or a recorded maze and hand it to `Solver`
+pub trait MazeBackend: Send + Sync {
+    /// the whole maze as it is right now, robot and target included
+    fn get_map(&self) -> impl Future<Output = eyre::Result<RawMap>> + Send;
+
+    /// tries to move one cell, resolving to whether the robot actually moved
+    fn move_cmd(&self, direction: MoveDirection)
+    -> impl Future<Output = eyre::Result<bool>> + Send;
+
+    /// puts the robot back at its start on the same maze
+    fn reset(&self) -> impl Future<Output = eyre::Result<()>> + Send;
+
+    /// live sensor frames; the receiver closes once the backend stops producing them
+    fn subscribe_sensors(&self) -> broadcast::Receiver<SensorsStates>;
+
+    /// the latest sensor frame, `None` until one arrives; closes like `subscribe_sensors`
+    ///
+    /// the default bridges `subscribe_sensors`, so it only sees frames from the call onwards.
+    /// backends that keep their own latest frame should hand that out instead
+    fn watch_sensors(&self) -> watch::Receiver<Option<SensorFrame>> {
+        let mut sensor_rx = self.subscribe_sensors();
+        let (frame_tx, frame_rx) = watch::channel(None);
+
+        tokio::spawn(async move {
+            let mut seq = 0;
+            loop {
+                let received = tokio::select! {
+                    received = sensor_rx.recv() => received,
+                    // every receiver was dropped, nobody is left to hand frames to
+                    _ = frame_tx.closed() => break,
+                };
+                match received {
+                    Ok(states) => {
+                        seq += 1;
+                        let frame = SensorFrame {
+                            seq,
+                            received_at: Instant::now(),
+                            states,
+                        };
+                        if frame_tx.send(Some(frame)).is_err() {
+                            break;
+                        }
+                    }
+                    // only the newest frame matters here
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        frame_rx
+    }
+
+    /// the most recent sensor frame without waiting, `None` only until the first one arrives
+    ///
+    /// required because only the backend knows its latest frame: a receiver from the default
+    /// `watch_sensors` starts out empty
+    fn latest_sensors(&self) -> Option<SensorFrame>;
+
+    /// fetches the map, retrying until it has exactly one robot and at least one target
+    ///
+    /// right after `--generate` the simulator can answer before the maze is fully built,
+    /// returning a partial or empty grid
+    fn get_ready_map(
+        &self,
+        timeout: Duration,
+    ) -> impl Future<Output = eyre::Result<RawMap>> + Send {
+        async move {
+            let deadline = tokio::time::Instant::now() + timeout;
+
+            loop {
+                let map = self.get_map().await?;
+                let count = |symbol: &str| {
+                    map.occupancy_grid_flattened
+                        .iter()
+                        .filter(|cell| cell.as_str() == symbol)
+                        .count()
+                };
+                let (robots, targets) = (count("r"), count("t"));
+
+                if robots == 1 && targets >= 1 {
+                    return Ok(map);
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    eyre::bail!(
+                        "map not ready after {:?}: {} robots and {} targets in a {} cell grid, \
+                         it may be malformed or only partially published",
+                        timeout,
+                        robots,
+                        targets,
+                        map.occupancy_grid_flattened.len()
+                    );
+                }
+
+                log::info!(
+                    "map not ready yet ({} robots, {} targets), waiting",
+                    robots,
+                    targets
+                );
+                tokio::time::sleep(MAP_RETRY_INTERVAL).await;
+            }
+        }
+    }
+
+    /// `move_cmd`, retrying a move the simulator refused up to `retries` more times with
+    /// `backoff` doubling in between
+    ///
+    /// right after a reset the simulator can refuse a legal move, so a single failure is not
+    /// always a wall. resolves to false only once every attempt failed
+    fn move_cmd_retry(
+        &self,
+        direction: MoveDirection,
+        retries: usize,
+        backoff: Duration,
+    ) -> impl Future<Output = eyre::Result<bool>> + Send {
+        async move {
+            for attempt in 1..=retries {
+                if self.move_cmd(direction).await? {
+                    return Ok(true);
+                }
+
+                let delay = backoff * 2u32.saturating_pow(attempt as u32 - 1);
+                log::debug!(
+                    "move {:?} refused, retrying in {:?} ({}/{})",
+                    direction,
+                    delay,
+                    attempt,
+                    retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+
+            self.move_cmd(direction).await
+        }
+    }
+
+    /// reports how far the run has got to whoever monitors it; does nothing unless the backend
+    /// has somewhere to send it
+    fn publish_progress(&self, _step: usize, _known_cells: usize) {}
+
+    /// whether one request can move the robot several cells; cg's move_command is single-cell
+    fn supports_continuous_moves(&self) -> bool {
+        false
+    }
+
+    /// moves in `direction` until blocked and returns how many cells were traversed
+    ///
+    /// without continuous-move support this falls back to single moves, stopping at the first
+    /// failed one, so it costs one request more than the cells traversed
+    fn move_until_wall(
+        &self,
+        direction: MoveDirection,
+    ) -> impl Future<Output = eyre::Result<usize>> + Send {
+        async move {
+            let mut traversed = 0;
+            while self.move_cmd(direction).await? {
+                traversed += 1;
+            }
+            Ok(traversed)
+        }
+    }
+}
+
+/// waits until `sensor_rx` holds a frame received at or after `since`, returning it right away
+/// if the current one already is
+///
+/// pass the instant the last move finished: earlier frames may show the robot before the move,
+/// while a frame that arrived since is kept instead of being drained along with stale ones
+pub async fn sensors_since(
+    sensor_rx: &mut watch::Receiver<Option<SensorFrame>>,
+    since: Instant,
+) -> eyre::Result<SensorFrame> {
+    let frame = sensor_rx
+        .wait_for(|frame| {
+            frame
+                .as_ref()
+                .is_some_and(|frame| frame.received_at >= since)
+        })
+        .await
+        .map_err(|_| eyre::eyre!("sensor channel closed"))?;
+    Ok(frame
+        .clone()
+        .expect("wait_for only returns a received frame"))
+}
@@ -1,6 +1,15 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand, ValueEnum};
 use log::LevelFilter;
 
+use solver::algorithms::pathfinding::{Heuristic, NeighborOrder};
+use solver::maze::{CoordStyle, CornerPolicy, DEFAULT_MAX_CELLS};
+use solver::report::BenchmarkFormat;
+use solver::ros::{DEFAULT_NODE_NAME, types::MoveDirection};
+use solver::solvers::{DEFAULT_MAX_STEPS, PlanUnknown};
+use solver::time_unit::TimeUnit;
+
 #[derive(Parser, Debug)]
 #[command(name = "the-honored-solver")]
 #[command(about = "Maze solver with multiple strategies")]
@@ -21,6 +30,163 @@ pub struct Args {
     #[arg(short, long, default_value_t = 0)]
     pub delay: u64,
 
+    /// Drive the executed path back to the start after reaching the target
+    #[arg(long)]
+    pub return_home: bool,
+
+    /// Write the planned path as (row, col) waypoints to this CSV file
+    #[arg(long, value_name = "PATH")]
+    pub waypoints: Option<PathBuf>,
+
+    /// Record the executed run as a versioned JSON trace to this file
+    #[arg(long, value_name = "PATH")]
+    pub record_trace: Option<PathBuf>,
+
+    /// Write the start, target, planned moves and timings of the run as JSON to this file
+    #[arg(long, value_name = "PATH")]
+    pub export_run: Option<PathBuf>,
+
+    /// Render the robot walking the planned path as an animated GIF to this file
+    #[arg(long, value_name = "PATH")]
+    pub export_gif: Option<PathBuf>,
+
+    /// Delay between GIF frames in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    pub gif_frame_delay: u32,
+
+    /// How long blind mode waits for the first sensor frame, in milliseconds
+    #[arg(long, default_value_t = 5000)]
+    pub sensor_timeout: u64,
+
+    /// In omniscient mode, compare live sensors with the fetched map after every move and warn
+    /// on the first disagreement
+    #[arg(long)]
+    pub verify_sensors: bool,
+
+    /// How long to keep retrying get_map until the maze has a robot and a target, in
+    /// milliseconds
+    #[arg(long, default_value_t = 5000)]
+    pub map_timeout: u64,
+
+    /// After every execution move, look up the robot's real cell in the map and abort as soon
+    /// as it is not where the plan put it
+    #[arg(long)]
+    pub abort_on_stray: bool,
+
+    /// Name of the ROS node the solver registers as
+    #[arg(long, default_value = DEFAULT_NODE_NAME)]
+    pub node_name: String,
+
+    /// ROS namespace to create the node in and to prefix every service and topic path with,
+    /// for simulators launched under a namespace
+    #[arg(long, value_name = "NS", default_value = "")]
+    pub namespace: String,
+
+    /// In omniscient mode, fetch the map and plan but never move the robot, reporting a zero
+    /// execution time; benchmarks then skip their resets too
+    #[arg(long)]
+    pub plan_only: bool,
+
+    /// In omniscient mode, reuse paths planned earlier on the same maze with the same
+    /// algorithm, caching them as files named by the maze's hash in this directory
+    #[arg(long, value_name = "DIR")]
+    pub path_cache: Option<PathBuf>,
+
+    /// After every move, publish the step number and known-cell count as json on
+    /// /<node-name>/progress (std_msgs/String)
+    #[arg(long)]
+    pub publish_progress: bool,
+
+    /// How long a get_map, move or reset request may take before failing, in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 5000)]
+    pub request_timeout: u64,
+
+    /// Retry a move the simulator refuses this many times, with a growing pause, before
+    /// treating it as failed
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub move_retries: usize,
+
+    /// In omniscient mode, fail on a map with an unrecognized cell symbol, or without a robot or
+    /// target, instead of reading odd cells as unknown and finding no path
+    #[arg(long)]
+    pub strict_map: bool,
+
+    /// Reject maps from get_map with more cells than this
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_CELLS)]
+    pub max_cells: usize,
+
+    /// Abort blind exploration after this many moves, and refuse to execute a longer planned
+    /// path in omniscient mode
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_STEPS)]
+    pub max_steps: usize,
+
+    /// Skip the maze reset before executing the optimal path in blind mode,
+    /// planning from wherever exploration ended instead of the origin
+    #[arg(long)]
+    pub no_reset_before_exec: bool,
+
+    /// Abort blind exploration if the explored region grows beyond N×N cells
+    #[arg(long, value_name = "N")]
+    pub max_extent: Option<usize>,
+
+    /// In blind mode, treat a failed exploration move as a wall the sensors misreported and
+    /// keep exploring instead of aborting
+    #[arg(long)]
+    pub tolerate_blocked_moves: bool,
+
+    /// In blind mode, follow a target that relocates during exploration and check it is still
+    /// there before the final move
+    #[arg(long)]
+    pub track_target: bool,
+
+    /// In blind mode, save the explored map to this file after exploration
+    #[arg(long, value_name = "PATH")]
+    pub save_explored: Option<PathBuf>,
+
+    /// In blind mode, log an ASCII rendering of the explored map after exploration
+    #[arg(long)]
+    pub dump_map: bool,
+
+    /// In blind mode, skip exploration and plan over a map saved with --save-explored, then
+    /// execute it on the live robot after a reset
+    #[arg(long, value_name = "PATH", conflicts_with = "save_explored")]
+    pub load_explored: Option<PathBuf>,
+
+    /// How blind mode plans over unexplored cells: as walls, or as open with replanning when a
+    /// move turns out blocked
+    #[arg(long, value_enum, default_value_t = PlanUnknown::Wall)]
+    pub plan_unknown: PlanUnknown,
+
+    /// Moves the composite explorer spends on its fallback strategy after detecting a loop
+    #[arg(long, default_value_t = 50)]
+    pub fallback_moves: usize,
+
+    /// Order the recursive backtracker tries unvisited neighbors in (all four directions)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "DIRS",
+        value_parser = parse_direction,
+        default_value = "up,down,left,right"
+    )]
+    pub explore_priority: Vec<MoveDirection>,
+
+    /// Corner-cutting policy for diagonal moves (applies to omniscient --diagonal)
+    #[arg(long, value_enum, default_value_t = CornerPolicy::Forbid)]
+    pub corner_policy: CornerPolicy,
+
+    /// How positions are written in logs: (row, col) or (x=col, y=row)
+    #[arg(long, value_enum, default_value_t = CoordStyle::Rowcol)]
+    pub coord_style: CoordStyle,
+
+    /// Make every move take this many extra milliseconds, for testing timing-sensitive features
+    #[arg(long, value_name = "MS", default_value_t = 0, hide = true)]
+    pub simulate_latency_ms: u64,
+
+    /// Unit for reported durations; auto picks one per value
+    #[arg(long, value_enum, default_value_t = TimeUnit::Auto)]
+    pub time_unit: TimeUnit,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -32,6 +198,28 @@ pub enum Command {
         /// Pathfinding algorithm to use
         #[arg(value_enum)]
         algorithm: PathfindingAlgorithm,
+
+        /// Heuristic A* steers by (astar only) [default: manhattan, chebyshev with --diagonal]
+        #[arg(long, value_enum)]
+        heuristic: Option<Heuristic>,
+
+        /// Among equally short paths prefer ones that keep going straight (astar only)
+        #[arg(long)]
+        straight_bias: bool,
+
+        /// Which neighbor DFS expands first, changing the route it finds (dfs only)
+        #[arg(long, value_enum)]
+        neighbor_order: Option<NeighborOrder>,
+
+        /// Plan 8-connected, taking diagonal steps allowed by --corner-policy; needs
+        /// --plan-only, since the simulator is not known to accept diagonal moves
+        #[arg(long)]
+        diagonal: bool,
+
+        /// Straighten the planned path before executing it, dropping moves that are undone
+        /// right away and cutting turns where walkable straight lines allow
+        #[arg(long)]
+        smooth: bool,
     },
 
     /// Blind mode: Explore using only sensors (no map knowledge)
@@ -43,43 +231,177 @@ pub enum Command {
         /// Pathfinding algorithm to use for optimal route
         #[arg(value_enum)]
         pathfinding: PathfindingAlgorithm,
+
+        /// Reset and re-run the same combination K times, reporting the spread of step counts
+        #[arg(long, value_name = "K", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        repeat: u64,
+    },
+
+    /// Preview mode: Load a maze from a file, plan a path and render it without ROS
+    #[command(visible_alias = "offline")]
+    PreviewFile {
+        /// Maze file (culling_games csv map or plain character grid)
+        path: PathBuf,
+
+        /// Pathfinding algorithm to use
+        #[arg(value_enum)]
+        algorithm: PathfindingAlgorithm,
+    },
+
+    /// Paths mode: List the K cheapest distinct routes through a maze file, without ROS
+    Paths {
+        /// Maze file (culling_games csv map or plain character grid)
+        path: PathBuf,
+
+        /// How many routes to list
+        #[arg(long, value_name = "N", default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..))]
+        k: u64,
+    },
+
+    /// Scaling mode: Plan on every maze file in a directory and report planning time against
+    /// maze size as CSV, without ROS
+    Scaling {
+        /// Pathfinding algorithm to measure
+        #[arg(value_enum)]
+        algorithm: PathfindingAlgorithm,
+
+        /// Directory of maze files (culling_games csv maps or plain character grids)
+        dir: PathBuf,
+
+        /// Write the CSV to this file instead of stdout
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+
+    /// Diff two JSON benchmark reports (from --report), flagging regressions and improvements
+    DiffReports {
+        /// Baseline report
+        old: PathBuf,
+
+        /// Report to compare against the baseline
+        new: PathBuf,
+    },
+
+    /// Compare mode: Solve the same maze with two algorithms and report which won
+    Compare {
+        /// First algorithm (pathfinding or exploration value name)
+        #[arg(value_parser = AlgorithmName::parse)]
+        a: AlgorithmName,
+
+        /// Second algorithm, of the same kind as the first
+        #[arg(value_parser = AlgorithmName::parse)]
+        b: AlgorithmName,
+
+        /// Pathfinding algorithm paired with both explorers when comparing exploration algorithms
+        #[arg(long, value_enum, default_value_t = PathfindingAlgorithm::AStar)]
+        pathfinding: PathfindingAlgorithm,
+
+        /// Plan both pathfinders on one fetched map without moving or resetting, so the results
+        /// differ only because of the algorithms (pathfinding only)
+        #[arg(long)]
+        same_map: bool,
     },
 
     /// Benchmark mode: Run all algorithms and compare performance
     Benchmark {
+        /// Run the algorithms in a random order to reduce order-dependent bias
+        #[arg(long)]
+        shuffle_order: bool,
+
+        /// Seed for --shuffle-order, for reproducible orderings
+        #[arg(long, requires = "shuffle_order")]
+        rng_seed: Option<u64>,
+
+        /// Solve with every algorithm N times, resetting in between, and report the mean and
+        /// standard deviation of steps and total time
+        #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        runs: u64,
+
+        /// Show how many up/down/left/right moves each algorithm's path contains
+        #[arg(long)]
+        direction_counts: bool,
+
+        /// Only run these algorithms (comma-separated value names, e.g. astar,dijkstra)
+        #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+        only: Vec<String>,
+
+        /// Skip these algorithms (comma-separated value names)
+        #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+        skip: Vec<String>,
+
+        /// Write every attempted run, failures included, to this JSON report
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
+
+        /// Also emit every completed run as csv or json, for plotting and regression tracking
+        #[arg(long, value_name = "FORMAT")]
+        benchmark_output: Option<BenchmarkFormat>,
+
+        /// Write the --benchmark-output results to this file instead of stdout
+        #[arg(long, value_name = "PATH", requires = "benchmark_output")]
+        benchmark_file: Option<PathBuf>,
+
+        /// Stop the sweep at the first failing algorithm and exit with its error
+        #[arg(long)]
+        fail_fast: bool,
+
         /// Mode to benchmark
         #[command(subcommand)]
         mode: BenchmarkMode,
     },
 }
 
-#[derive(Subcommand, Debug)]
+#[derive(Subcommand, Debug, Clone)]
 pub enum BenchmarkMode {
     /// Benchmark all omniscient pathfinding algorithms
     Omniscient,
 
     /// Benchmark all blind exploration algorithms
     Blind,
+
+    /// Sweep weighted A* over several heuristic weights on the same maze
+    OmniscientEpsilon {
+        /// Heuristic weights to try (comma-separated, each at least 1.0)
+        #[arg(long, value_delimiter = ',', default_values_t = [1.0, 1.25, 1.5, 2.0])]
+        epsilons: Vec<f64>,
+    },
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum PathfindingAlgorithm {
-    /// A* algorithm with Manhattan distance heuristic
+    /// A* algorithm, steered by the heuristic picked with --heuristic
     #[value(name = "astar", alias = "a-star")]
     AStar,
 
     /// Dijkstra's shortest path algorithm
     Dijkstra,
 
-    /// Depth-First Search
+    /// Depth-First Search (finds a path, not necessarily the shortest)
     #[value(name = "dfs")]
     #[allow(clippy::upper_case_acronyms)]
     DFS,
+
+    /// Breadth-First Search
+    #[value(name = "bfs")]
+    #[allow(clippy::upper_case_acronyms)]
+    BFS,
+
+    /// Iterative-deepening DFS, shortest paths by re-searching with a growing depth bound
+    #[value(name = "iddfs")]
+    #[allow(clippy::upper_case_acronyms)]
+    IDDFS,
 }
 
 impl PathfindingAlgorithm {
     pub fn all() -> impl Iterator<Item = Self> {
-        [Self::AStar, Self::Dijkstra, Self::DFS].into_iter()
+        [
+            Self::AStar,
+            Self::Dijkstra,
+            Self::DFS,
+            Self::BFS,
+            Self::IDDFS,
+        ]
+        .into_iter()
     }
 
     pub fn name(&self) -> &'static str {
@@ -87,30 +409,235 @@ impl PathfindingAlgorithm {
             Self::AStar => "A*",
             Self::Dijkstra => "Dijkstra",
             Self::DFS => "DFS",
+            Self::BFS => "BFS",
+            Self::IDDFS => "IDDFS",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum ExplorationAlgorithm {
     /// Wall follower using left-hand rule
     #[value(name = "wall-follower")]
     WallFollower,
 
+    /// Wall follower using right-hand rule
+    #[value(name = "wall-follower-right")]
+    WallFollowerRight,
+
     /// Recursive backtracker (DFS-based exploration)
     #[value(name = "recursive-backtracker")]
     RecursiveBacktracker,
+
+    /// Wall follower that falls back to the recursive backtracker when it loops
+    Composite,
+
+    /// Online spanning-tree coverage that avoids retracing finished branches
+    #[value(name = "spanning-tree")]
+    SpanningTree,
+
+    /// Outward square spiral from the start, detouring to the nearest unvisited cell when blocked
+    Spiral,
+
+    /// Pledge algorithm: heads up and follows walls by turn count until the target is adjacent
+    Pledge,
+
+    /// Frontier exploration: heads for the nearest known free cell bordering unknown space
+    Frontier,
+
+    /// Tremaux's algorithm: marks passages as they are walked and never walks one a third time
+    Tremaux,
 }
 
 impl ExplorationAlgorithm {
     pub fn all() -> impl Iterator<Item = Self> {
-        [Self::WallFollower, Self::RecursiveBacktracker].into_iter()
+        [
+            Self::WallFollower,
+            Self::WallFollowerRight,
+            Self::RecursiveBacktracker,
+            Self::Composite,
+            Self::SpanningTree,
+            Self::Spiral,
+            Self::Pledge,
+            Self::Frontier,
+            Self::Tremaux,
+        ]
+        .into_iter()
     }
 
     pub fn name(&self) -> &'static str {
         match self {
             Self::WallFollower => "Wall Follower",
+            Self::WallFollowerRight => "Wall Follower (Right)",
             Self::RecursiveBacktracker => "Recursive Backtracker",
+            Self::Composite => "Composite",
+            Self::SpanningTree => "Spanning Tree Coverage",
+            Self::Spiral => "Spiral",
+            Self::Pledge => "Pledge",
+            Self::Frontier => "Frontier Exploration",
+            Self::Tremaux => "Tremaux",
         }
     }
 }
+
+fn parse_direction(name: &str) -> Result<MoveDirection, String> {
+    MoveDirection::try_from(name).map_err(|e| format!("{}, expected up, down, left or right", e))
+}
+
+/// subset of algorithms a benchmark runs, from --only / --skip
+///
+/// in blind mode, `--only` restricts a kind (exploration or pathfinding) only when it names
+/// at least one algorithm of that kind, so `--only astar` keeps every explorer paired with A*
+#[derive(Debug, Default)]
+pub struct BenchmarkFilter {
+    only_exploration: Vec<ExplorationAlgorithm>,
+    only_pathfinding: Vec<PathfindingAlgorithm>,
+    skip_exploration: Vec<ExplorationAlgorithm>,
+    skip_pathfinding: Vec<PathfindingAlgorithm>,
+}
+
+impl BenchmarkFilter {
+    pub fn parse(mode: &BenchmarkMode, only: &[String], skip: &[String]) -> eyre::Result<Self> {
+        let mut filter = Self::default();
+
+        if matches!(mode, BenchmarkMode::OmniscientEpsilon { .. })
+            && !(only.is_empty() && skip.is_empty())
+        {
+            eyre::bail!("--only/--skip don't apply to the omniscient-epsilon sweep");
+        }
+
+        for (names, exploration, pathfinding) in [
+            (
+                only,
+                &mut filter.only_exploration,
+                &mut filter.only_pathfinding,
+            ),
+            (
+                skip,
+                &mut filter.skip_exploration,
+                &mut filter.skip_pathfinding,
+            ),
+        ] {
+            for name in names {
+                if let Ok(algorithm) = PathfindingAlgorithm::from_str(name, true) {
+                    pathfinding.push(algorithm);
+                } else if let (BenchmarkMode::Blind, Ok(algorithm)) =
+                    (mode, ExplorationAlgorithm::from_str(name, true))
+                {
+                    exploration.push(algorithm);
+                } else {
+                    eyre::bail!(
+                        "unknown algorithm '{}', valid options: {}",
+                        name,
+                        value_names(matches!(mode, BenchmarkMode::Blind)).join(", ")
+                    );
+                }
+            }
+        }
+
+        Ok(filter)
+    }
+
+    pub fn allows_exploration(&self, algorithm: ExplorationAlgorithm) -> bool {
+        (self.only_exploration.is_empty() || self.only_exploration.contains(&algorithm))
+            && !self.skip_exploration.contains(&algorithm)
+    }
+
+    pub fn allows_pathfinding(&self, algorithm: PathfindingAlgorithm) -> bool {
+        (self.only_pathfinding.is_empty() || self.only_pathfinding.contains(&algorithm))
+            && !self.skip_pathfinding.contains(&algorithm)
+    }
+}
+
+/// an algorithm named on the command line, of either kind
+#[derive(Debug, Clone, Copy)]
+pub enum AlgorithmName {
+    Pathfinding(PathfindingAlgorithm),
+    Exploration(ExplorationAlgorithm),
+}
+
+impl AlgorithmName {
+    pub fn same_kind(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Pathfinding(_), Self::Pathfinding(_))
+                | (Self::Exploration(_), Self::Exploration(_))
+        )
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        if let Ok(algorithm) = PathfindingAlgorithm::from_str(name, true) {
+            Ok(Self::Pathfinding(algorithm))
+        } else if let Ok(algorithm) = ExplorationAlgorithm::from_str(name, true) {
+            Ok(Self::Exploration(algorithm))
+        } else {
+            Err(format!(
+                "unknown algorithm '{}', valid options: {}",
+                name,
+                value_names(true).join(", ")
+            ))
+        }
+    }
+}
+
+/// clap value names of every pathfinding algorithm, plus every explorer when `exploration` is set
+fn value_names(exploration: bool) -> Vec<String> {
+    let pathfinding = PathfindingAlgorithm::value_variants()
+        .iter()
+        .filter_map(|v| v.to_possible_value());
+    let explorers = ExplorationAlgorithm::value_variants()
+        .iter()
+        .filter_map(|v| v.to_possible_value())
+        .filter(|_| exploration);
+
+    explorers
+        .chain(pathfinding)
+        .map(|v| v.get_name().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blind_takes_an_explorer_then_a_pathfinder() {
+        let args = Args::try_parse_from([
+            "the-honored-solver",
+            "blind",
+            "recursive-backtracker",
+            "dijkstra",
+        ])
+        .unwrap();
+
+        let Command::Blind {
+            exploration,
+            pathfinding,
+            repeat,
+        } = args.command
+        else {
+            panic!("parsed as {:?}", args.command);
+        };
+        assert_eq!(exploration, ExplorationAlgorithm::RecursiveBacktracker);
+        assert_eq!(pathfinding, PathfindingAlgorithm::Dijkstra);
+        assert_eq!(repeat, 1);
+    }
+
+    #[test]
+    fn same_map_is_a_compare_flag_of_its_own() {
+        let args = Args::try_parse_from([
+            "the-honored-solver",
+            "compare",
+            "astar",
+            "iddfs",
+            "--same-map",
+        ])
+        .unwrap();
+
+        let Command::Compare { same_map, .. } = args.command else {
+            panic!("parsed as {:?}", args.command);
+        };
+        assert!(same_map);
+        assert!(!args.plan_only);
+    }
+}
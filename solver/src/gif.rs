@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use crate::{
+    algorithms::pathfinding::path_to_waypoints,
+    maze::{BoundedMaze, Cell, Position},
+    ros::types::MoveDirection,
+};
+
+/// side of one maze cell in pixels
+const CELL_PX: usize = 8;
+/// longer paths are subsampled down to this many frames, always keeping the last one
+pub const MAX_FRAMES: usize = 300;
+
+/// palette index per thing drawn; the table below must stay in the same order
+const FREE: u8 = 0;
+const BLOCKED: u8 = 1;
+const UNKNOWN: u8 = 2;
+const TARGET: u8 = 3;
+const TRAIL: u8 = 4;
+const ROBOT: u8 = 5;
+
+const PALETTE: [[u8; 3]; 8] = [
+    [0xff, 0xff, 0xff],
+    [0x30, 0x30, 0x30],
+    [0xb0, 0xb0, 0xb0],
+    [0xe0, 0x30, 0x30],
+    [0xa0, 0xc8, 0xf0],
+    [0x20, 0x50, 0xd0],
+    [0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00],
+];
+/// 8 palette entries need 3 bits per pixel
+const MIN_CODE_SIZE: u8 = 3;
+const MAX_CODE: u16 = 4096;
+
+/// renders the robot walking `path` from `start` as a looping animated GIF, one frame per step
+///
+/// paths longer than `MAX_FRAMES` steps are subsampled evenly so the file stays small
+pub fn export_gif(
+    maze: &BoundedMaze,
+    start: Position,
+    path: &[MoveDirection],
+    out: &Path,
+    frame_delay_ms: u32,
+) -> eyre::Result<usize> {
+    let width = maze.width() * CELL_PX;
+    let height = maze.height() * CELL_PX;
+    let (Ok(screen_width), Ok(screen_height)) = (u16::try_from(width), u16::try_from(height))
+    else {
+        eyre::bail!("maze too large for a gif ({}x{} pixels)", width, height);
+    };
+
+    let waypoints = path_to_waypoints(start, path);
+    let frames = frame_indices(waypoints.len());
+    let delay = (frame_delay_ms / 10).min(u16::MAX as u32) as u16;
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"GIF89a");
+    bytes.extend_from_slice(&screen_width.to_le_bytes());
+    bytes.extend_from_slice(&screen_height.to_le_bytes());
+    // global color table of 2^(2+1) entries, 3 bits of color resolution
+    bytes.extend_from_slice(&[0b1010_0010, 0, 0]);
+    bytes.extend(PALETTE.iter().flatten());
+    // NETSCAPE2.0 application extension: loop forever
+    bytes.extend_from_slice(&[0x21, 0xff, 0x0b]);
+    bytes.extend_from_slice(b"NETSCAPE2.0");
+    bytes.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for &frame in &frames {
+        let pixels = render_frame(maze, &waypoints[..=frame], width, height);
+
+        bytes.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+        bytes.extend_from_slice(&delay.to_le_bytes());
+        bytes.extend_from_slice(&[0x00, 0x00]);
+
+        bytes.push(0x2c);
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        bytes.extend_from_slice(&screen_width.to_le_bytes());
+        bytes.extend_from_slice(&screen_height.to_le_bytes());
+        bytes.push(0x00);
+
+        bytes.push(MIN_CODE_SIZE);
+        for block in lzw_encode(&pixels).chunks(255) {
+            bytes.push(block.len() as u8);
+            bytes.extend_from_slice(block);
+        }
+        bytes.push(0x00);
+    }
+
+    bytes.push(0x3b);
+    std::fs::write(out, bytes)?;
+    Ok(frames.len())
+}
+
+/// waypoint indices to draw, at most `MAX_FRAMES`, spread evenly and ending on the last one
+fn frame_indices(waypoints: usize) -> Vec<usize> {
+    if waypoints <= MAX_FRAMES {
+        return (0..waypoints).collect();
+    }
+
+    let last = waypoints - 1;
+    (0..MAX_FRAMES)
+        .map(|frame| frame * last / (MAX_FRAMES - 1))
+        .collect()
+}
+
+/// one palette index per pixel, row-major; `walked` ends at the robot's current cell
+fn render_frame(maze: &BoundedMaze, walked: &[Position], width: usize, height: usize) -> Vec<u8> {
+    let mut cells: Vec<u8> = (0..maze.height() * maze.width())
+        .map(
+            |idx| match maze.get(Position::from_index(idx, maze.width())) {
+                Some(Cell::Blocked) => BLOCKED,
+                Some(Cell::Unknown) | None => UNKNOWN,
+                Some(Cell::Target) => TARGET,
+                // the snapshot's robot cell is only where the run started
+                Some(Cell::Free | Cell::Slow | Cell::Robot) => FREE,
+            },
+        )
+        .collect();
+
+    for pos in walked {
+        let idx = pos.to_index(maze.width());
+        if cells[idx] == FREE {
+            cells[idx] = TRAIL;
+        }
+    }
+    if let Some(robot) = walked.last() {
+        cells[robot.to_index(maze.width())] = ROBOT;
+    }
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let row = (y / CELL_PX) * maze.width();
+        for x in 0..width {
+            pixels.push(cells[row + x / CELL_PX]);
+        }
+    }
+    pixels
+}
+
+/// variable-width LZW as GIF expects it, packed least significant bit first
+fn lzw_encode(pixels: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+
+    let mut writer = BitWriter::default();
+    // code for prefix + pixel at `prefix * PALETTE.len() + pixel`, 0 when absent (no real
+    // code is that low)
+    let mut table = vec![0u16; MAX_CODE as usize * PALETTE.len()];
+    let mut next = end + 1;
+
+    writer.write(clear, code_width(next, end));
+
+    let Some((&first, rest)) = pixels.split_first() else {
+        writer.write(end, code_width(next, end));
+        return writer.finish();
+    };
+
+    let mut prefix = first as u16;
+    for &pixel in rest {
+        let slot = prefix as usize * PALETTE.len() + pixel as usize;
+        if table[slot] != 0 {
+            prefix = table[slot];
+            continue;
+        }
+
+        writer.write(prefix, code_width(next, end));
+        if next < MAX_CODE {
+            table[slot] = next;
+            next += 1;
+        } else {
+            writer.write(clear, code_width(next, end));
+            table.fill(0);
+            next = end + 1;
+        }
+        prefix = pixel as u16;
+    }
+
+    writer.write(prefix, code_width(next, end));
+    writer.write(end, code_width(next, end));
+    writer.finish()
+}
+
+/// width the decoder reads the next code at
+///
+/// the decoder adds each table entry one code later than the encoder, so it sees one entry
+/// fewer whenever the encoder has added any since the last clear
+fn code_width(next: u16, end: u16) -> u8 {
+    let decoder_next = if next > end + 1 { next - 1 } else { next };
+    let mut width = MIN_CODE_SIZE + 1;
+    while width < 12 && decoder_next >= 1 << width {
+        width += 1;
+    }
+    width
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
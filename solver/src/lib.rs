@@ -0,0 +1,60 @@
+pub mod algorithms;
+pub mod backend;
+pub mod gif;
+pub mod maze;
+pub mod mock;
+pub mod report;
+pub mod ros;
+pub mod solvers;
+pub mod time_unit;
+pub mod trace;
+
+use std::sync::Arc;
+
+pub use algorithms::pathfinding::PathResult;
+pub use backend::MazeBackend;
+
+use algorithms::{exploration::ExplorationAlgorithm, pathfinding::PathfindingAlgorithm};
+use solvers::{BlindConfig, BlindSolver, OmniscientConfig, OmniscientSolver};
+
+/// entry point for embedding the solver: runs either mode against one backend
+///
+/// the backend is shared, so consecutive solves see whatever state the previous one left the
+/// maze in; call `MazeBackend::reset` between them to start over
+pub struct Solver<B: MazeBackend> {
+    backend: Arc<B>,
+}
+
+impl<B: MazeBackend> Solver<B> {
+    pub fn new(backend: Arc<B>) -> Self {
+        Self { backend }
+    }
+
+    pub fn backend(&self) -> &Arc<B> {
+        &self.backend
+    }
+
+    /// fetches the whole map, plans over it with `algorithm` and drives the robot to the target
+    pub async fn solve_omniscient<A: PathfindingAlgorithm>(
+        &self,
+        algorithm: A,
+        config: OmniscientConfig,
+    ) -> eyre::Result<PathResult> {
+        OmniscientSolver::new(algorithm, config)
+            .solve(self.backend.clone())
+            .await
+    }
+
+    /// explores with `exploration` using only sensors, then plans with `pathfinding` over what
+    /// was discovered and drives the robot to the target
+    pub async fn solve_blind<E: ExplorationAlgorithm, P: PathfindingAlgorithm>(
+        &self,
+        exploration: E,
+        pathfinding: P,
+        config: BlindConfig,
+    ) -> eyre::Result<PathResult> {
+        BlindSolver::new(exploration, pathfinding, config)
+            .solve(self.backend.clone())
+            .await
+    }
+}
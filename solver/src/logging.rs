@@ -1,14 +1,19 @@
 use std::io::{Error, Write};
 
-use colog::format::CologStyle;
-use colog::formatter;
+#[cfg(not(feature = "structured-logs"))]
+use colog::{format::CologStyle, formatter};
+#[cfg(not(feature = "structured-logs"))]
 use colored::Colorize;
 use env_logger::fmt::Formatter;
 use env_logger::{Builder, Target, WriteStyle};
-use log::{Level, LevelFilter, Record};
+#[cfg(not(feature = "structured-logs"))]
+use log::Level;
+use log::{LevelFilter, Record};
 
+#[cfg(not(feature = "structured-logs"))]
 struct CustomLevelTokens;
 
+#[cfg(not(feature = "structured-logs"))]
 impl CologStyle for CustomLevelTokens {
     fn level_token(&self, level: &Level) -> &str {
         match *level {
@@ -66,15 +71,70 @@ impl CologStyle for CustomLevelTokens {
     }
 }
 
+/// one json object per line carrying the message and every structured field on the record
+#[cfg(feature = "structured-logs")]
+fn format_structured(buf: &mut Formatter, record: &Record<'_>) -> Result<(), Error> {
+    use log::kv::{Key, Value, VisitSource};
+    use serde_json::{Map, Value as JsonValue};
+
+    struct Fields<'a>(&'a mut Map<String, JsonValue>);
+
+    impl<'kvs> VisitSource<'kvs> for Fields<'_> {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+            let json = if let Some(n) = value.to_u64() {
+                JsonValue::from(n)
+            } else if let Some(n) = value.to_i64() {
+                JsonValue::from(n)
+            } else if let Some(b) = value.to_bool() {
+                JsonValue::from(b)
+            } else {
+                JsonValue::from(value.to_string())
+            };
+            self.0.insert(key.to_string(), json);
+            Ok(())
+        }
+    }
+
+    let mut line = Map::new();
+    line.insert(
+        "ts".into(),
+        chrono::Local::now()
+            .format("%Y-%m-%dT%H:%M:%S%.6f%:z")
+            .to_string()
+            .into(),
+    );
+    line.insert(
+        "level".into(),
+        record.level().as_str().to_lowercase().into(),
+    );
+    line.insert("msg".into(), record.args().to_string().into());
+    record
+        .key_values()
+        .visit(&mut Fields(&mut line))
+        .map_err(Error::other)?;
+
+    writeln!(buf, "{}", JsonValue::Object(line))
+}
+
 pub struct Logger;
 
 impl Logger {
     pub fn init(level: LevelFilter) {
-        Builder::new()
+        let mut builder = Builder::new();
+        builder
             .filter("solver".into(), level)
-            .target(Target::Stdout)
+            .target(Target::Stdout);
+
+        #[cfg(feature = "structured-logs")]
+        builder
+            .format(format_structured)
+            .write_style(WriteStyle::Never);
+
+        #[cfg(not(feature = "structured-logs"))]
+        builder
             .format(formatter(CustomLevelTokens))
-            .write_style(WriteStyle::Always)
-            .init();
+            .write_style(WriteStyle::Always);
+
+        builder.init();
     }
 }
@@ -1,27 +1,139 @@
-mod algorithms;
 mod cli;
 mod logging;
-mod maze;
-mod ros;
-mod solvers;
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use eyre::Result;
 use log::{debug, info};
 
-use algorithms::{exploration, pathfinding};
-use cli::{Args, BenchmarkMode, Command as CliCommand, ExplorationAlgorithm, PathfindingAlgorithm};
+use cli::{
+    AlgorithmName, Args, BenchmarkFilter, BenchmarkMode, Command as CliCommand,
+    ExplorationAlgorithm, PathfindingAlgorithm,
+};
 use logging::Logger;
-use ros::ROSInterface;
-use solvers::{BlindSolver, OmniscientSolver};
+use solver::{
+    MazeBackend, Solver,
+    algorithms::{
+        pathfinding,
+        registry::{self, ExplorerOptions},
+    },
+    gif,
+    maze::{BoundedMaze, Position},
+    report,
+    report::{
+        BenchmarkFormat, BenchmarkRecord, BenchmarkReport, BenchmarkStats, StepStats,
+        write_benchmark_results,
+    },
+    ros::{self, InteractionStats, ROSInterface, RosConfig},
+    solvers::{BlindConfig, OmniscientConfig},
+    time_unit::Elapsed,
+    trace::{RunExport, TraceFile},
+};
 
 #[tokio::main]
 #[macros::with_node]
 async fn main() -> Result<()> {
     let args = Args::parse();
     Logger::init(args.verbosity);
+    args.time_unit.init();
+    args.coord_style.init();
+
+    let exports = Exports {
+        waypoints: args.waypoints.as_deref(),
+        record_trace: args.record_trace.as_deref(),
+        run: args.export_run.as_deref(),
+        gif: args.export_gif.as_deref(),
+        gif_frame_delay: args.gif_frame_delay,
+    };
+
+    if let CliCommand::PreviewFile {
+        ref path,
+        algorithm,
+    } = args.command
+    {
+        return preview_file(path, algorithm, &exports);
+    }
+    if let CliCommand::Paths { ref path, k } = args.command {
+        return list_paths(path, k as usize);
+    }
+    if let CliCommand::Scaling {
+        algorithm,
+        ref dir,
+        ref output,
+    } = args.command
+    {
+        return run_scaling(algorithm, dir, output.as_deref());
+    }
+    if let CliCommand::DiffReports { ref old, ref new } = args.command {
+        return diff_reports(old, new);
+    }
+
+    let benchmark_filter = benchmark_filter(&args)?;
+    let explorer_options = explorer_options(&args)?;
+    if let CliCommand::Omniscient {
+        algorithm,
+        heuristic: Some(_),
+        ..
+    } = args.command
+        && algorithm != PathfindingAlgorithm::AStar
+    {
+        eyre::bail!(
+            "--heuristic only applies to astar, got {}",
+            algorithm.name()
+        );
+    }
+    if let CliCommand::Omniscient {
+        algorithm,
+        straight_bias: true,
+        ..
+    } = args.command
+        && algorithm != PathfindingAlgorithm::AStar
+    {
+        eyre::bail!(
+            "--straight-bias only applies to astar, got {}",
+            algorithm.name()
+        );
+    }
+    if let CliCommand::Omniscient {
+        algorithm,
+        neighbor_order: Some(_),
+        ..
+    } = args.command
+        && algorithm != PathfindingAlgorithm::DFS
+    {
+        eyre::bail!(
+            "--neighbor-order only applies to dfs, got {}",
+            algorithm.name()
+        );
+    }
+    if matches!(args.command, CliCommand::Omniscient { diagonal: true, .. }) && !args.plan_only {
+        eyre::bail!(
+            "--diagonal needs --plan-only: the cg simulator's move service is only known to \
+             take up, down, left and right"
+        );
+    }
+    if let CliCommand::Compare { a, b, .. } = &args.command
+        && !a.same_kind(b)
+    {
+        eyre::bail!(
+            "compare needs two algorithms of the same kind, got {:?} and {:?}",
+            a,
+            b
+        );
+    }
+    if let CliCommand::Compare {
+        a, same_map: true, ..
+    } = &args.command
+        && !matches!(a, AlgorithmName::Pathfinding(_))
+    {
+        eyre::bail!(
+            "--same-map only applies to pathfinding algorithms, got {:?}",
+            a
+        );
+    }
 
     let mut cg_command = Command::new("ros2")
         .arg("run")
@@ -33,35 +145,172 @@ async fn main() -> Result<()> {
         .spawn()?;
 
     info!("throughout heaven and earth, i alone am the honored solver.");
+    debug!("corner policy: {:?}", args.corner_policy);
 
-    let ros = ROSInterface::new(&mut node)?;
-    start_node(node);
+    let ros_config = RosConfig::new(&args.node_name, &args.namespace);
+    debug!("ros config: {:?}", ros_config);
+    let ros = ROSInterface::new(
+        &ros_config,
+        start_node(&ros_config.node_name, &ros_config.namespace)?,
+        std::time::Duration::from_millis(args.simulate_latency_ms),
+        std::time::Duration::from_millis(args.request_timeout),
+        args.publish_progress,
+    )?;
+    if args.simulate_latency_ms > 0 {
+        log::warn!(
+            "simulating {} ms of extra latency on every move",
+            args.simulate_latency_ms
+        );
+    }
     ros.init().await?;
+    let solver = Solver::new(ros);
+
+    let omniscient_config = omniscient_config(&args);
+    let blind_config = blind_config(&args);
 
     match args.command {
-        CliCommand::Omniscient { algorithm } => {
-            run_omniscient_solver(ros, algorithm, args.delay).await?;
+        CliCommand::Omniscient {
+            algorithm,
+            heuristic,
+            straight_bias,
+            neighbor_order,
+            diagonal,
+            ..
+        } => {
+            let heuristic = diagonal_heuristic(algorithm, heuristic, diagonal);
+            run_omniscient_solver(
+                &solver,
+                algorithm,
+                heuristic,
+                straight_bias,
+                neighbor_order,
+                omniscient_config,
+                &exports,
+            )
+            .await?;
         }
         CliCommand::Blind {
             exploration,
             pathfinding,
+            repeat,
         } => {
-            run_blind_solver(ros, exploration, pathfinding, args.delay).await?;
+            run_blind_solver(
+                &solver,
+                exploration,
+                pathfinding,
+                blind_config,
+                explorer_options,
+                repeat,
+                &exports,
+            )
+            .await?;
         }
-        CliCommand::Benchmark { mode } => match mode {
-            BenchmarkMode::Omniscient => {
-                run_omniscient_benchmark(ros, args.delay).await?;
-            }
-            BenchmarkMode::Blind => {
-                run_blind_benchmark(ros, args.delay).await?;
+        CliCommand::PreviewFile { .. }
+        | CliCommand::Paths { .. }
+        | CliCommand::Scaling { .. }
+        | CliCommand::DiffReports { .. } => {
+            unreachable!("offline modes never start ros")
+        }
+        CliCommand::Compare {
+            a,
+            b,
+            pathfinding,
+            same_map,
+        } => {
+            run_compare(
+                &solver,
+                [a, b],
+                pathfinding,
+                same_map,
+                omniscient_config,
+                blind_config,
+                explorer_options,
+            )
+            .await?;
+        }
+        CliCommand::Benchmark {
+            mode,
+            shuffle_order,
+            rng_seed,
+            direction_counts,
+            ref report,
+            runs,
+            benchmark_output,
+            ref benchmark_file,
+            fail_fast,
+            ..
+        } => {
+            let options = BenchmarkOptions {
+                shuffle_seed: shuffle_order.then(|| rng_seed.unwrap_or_else(random_seed)),
+                direction_counts,
+                report: report.as_deref(),
+                runs: runs as usize,
+                output: benchmark_output,
+                output_file: benchmark_file.as_deref(),
+                fail_fast,
+            };
+
+            match mode {
+                BenchmarkMode::Omniscient => {
+                    run_omniscient_benchmark(
+                        &solver,
+                        omniscient_config,
+                        &benchmark_filter,
+                        &options,
+                    )
+                    .await?;
+                }
+                BenchmarkMode::OmniscientEpsilon { ref epsilons } => {
+                    run_epsilon_benchmark(&solver, omniscient_config, epsilons, &options).await?;
+                }
+                BenchmarkMode::Blind => {
+                    run_blind_benchmark(
+                        &solver,
+                        blind_config,
+                        explorer_options,
+                        &benchmark_filter,
+                        &options,
+                    )
+                    .await?;
+                }
             }
-        },
+        }
     }
 
-    cg_command.wait()?;
+    let status = match cg_command.try_wait()? {
+        Some(status) => {
+            debug!("simulator had already exited ({})", status);
+            status
+        }
+        None => cg_command.wait()?,
+    };
+
+    if let Some(code) = cg_failure_code(status) {
+        log::warn!(
+            "simulator exited with {}, the solver itself succeeded",
+            status
+        );
+        std::process::exit(code);
+    }
     Ok(())
 }
 
+/// exit code to propagate when the simulator died on its own
+///
+/// a clean exit, or termination by SIGINT/SIGTERM (ctrl-c or closing the simulator
+/// deliberately), is not a failure
+fn cg_failure_code(status: std::process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+
+    const SIGINT: i32 = 2;
+    const SIGTERM: i32 = 15;
+
+    if status.success() || matches!(status.signal(), Some(SIGINT | SIGTERM)) {
+        return None;
+    }
+    Some(status.code().unwrap_or(1))
+}
+
 fn build_cg_args(args: &Args) -> Vec<String> {
     let mut cg_args = vec!["--".to_string()];
 
@@ -78,218 +327,1235 @@ fn build_cg_args(args: &Args) -> Vec<String> {
     cg_args
 }
 
+fn blind_config(args: &Args) -> BlindConfig {
+    BlindConfig {
+        delay: std::time::Duration::from_millis(args.delay),
+        sensor_timeout: std::time::Duration::from_millis(args.sensor_timeout),
+        reset_before_exec: !args.no_reset_before_exec,
+        max_extent: args.max_extent,
+        return_home: args.return_home,
+        tolerate_blocked_moves: args.tolerate_blocked_moves,
+        plan_unknown: args.plan_unknown,
+        track_target: args.track_target,
+        save_explored: args.save_explored.clone(),
+        load_explored: args.load_explored.clone(),
+        abort_on_stray: args.abort_on_stray,
+        dump_map: args.dump_map,
+        move_retries: args.move_retries,
+        max_steps: args.max_steps,
+    }
+}
+
+fn explorer_options(args: &Args) -> Result<ExplorerOptions> {
+    let priority: [ros::types::MoveDirection; 4] = args
+        .explore_priority
+        .clone()
+        .try_into()
+        .map_err(|_| eyre::eyre!("--explore-priority needs exactly four directions"))?;
+    if let Some(missing) = ros::types::MoveDirection::all()
+        .into_iter()
+        .find(|direction| !priority.contains(direction))
+    {
+        eyre::bail!(
+            "--explore-priority must name every direction once, {} is missing",
+            missing.as_str()
+        );
+    }
+
+    Ok(ExplorerOptions {
+        fallback_moves: args.fallback_moves,
+        priority,
+    })
+}
+
+fn benchmark_filter(args: &Args) -> Result<BenchmarkFilter> {
+    match &args.command {
+        CliCommand::Benchmark {
+            mode, only, skip, ..
+        } => BenchmarkFilter::parse(mode, only, skip),
+        _ => Ok(BenchmarkFilter::default()),
+    }
+}
+
+/// A* defaults to chebyshev with diagonal moves, the only heuristic still admissible there
+fn diagonal_heuristic(
+    algorithm: PathfindingAlgorithm,
+    heuristic: Option<pathfinding::Heuristic>,
+    diagonal: bool,
+) -> Option<pathfinding::Heuristic> {
+    if !diagonal || algorithm != PathfindingAlgorithm::AStar {
+        return heuristic;
+    }
+
+    match heuristic {
+        None => Some(pathfinding::Heuristic::Chebyshev),
+        Some(pathfinding::Heuristic::Chebyshev) => heuristic,
+        Some(other) => {
+            log::warn!(
+                "{:?} overestimates with diagonal moves, paths may not be shortest",
+                other
+            );
+            heuristic
+        }
+    }
+}
+
+fn omniscient_config(args: &Args) -> OmniscientConfig {
+    let diagonal = matches!(args.command, CliCommand::Omniscient { diagonal: true, .. });
+    let smooth = matches!(args.command, CliCommand::Omniscient { smooth: true, .. });
+
+    OmniscientConfig {
+        delay: std::time::Duration::from_millis(args.delay),
+        return_home: args.return_home,
+        verify_sensors: args.verify_sensors,
+        max_cells: args.max_cells,
+        map_timeout: std::time::Duration::from_millis(args.map_timeout),
+        abort_on_stray: args.abort_on_stray,
+        diagonal: diagonal.then_some(args.corner_policy),
+        move_retries: args.move_retries,
+        smooth,
+        plan_only: args.plan_only,
+        path_cache: args.path_cache.clone(),
+        strict_map: args.strict_map,
+        max_steps: args.max_steps,
+    }
+}
+
 // ========== Omniscient Solvers ==========
 
 async fn solve_omniscient(
-    ros: std::sync::Arc<ROSInterface>,
+    solver: &Solver<ROSInterface>,
     algorithm: PathfindingAlgorithm,
-    delay: u64,
+    config: OmniscientConfig,
 ) -> Result<pathfinding::PathResult> {
-    match algorithm {
-        PathfindingAlgorithm::AStar => {
-            OmniscientSolver::new(pathfinding::AStar, delay)
-                .solve(ros)
-                .await
-        }
-        PathfindingAlgorithm::Dijkstra => {
-            OmniscientSolver::new(pathfinding::Dijkstra, delay)
-                .solve(ros)
-                .await
-        }
-        PathfindingAlgorithm::DFS => {
-            OmniscientSolver::new(pathfinding::DFS, delay)
-                .solve(ros)
-                .await
-        }
-    }
+    solver
+        .solve_omniscient(pathfinder(algorithm)?, config)
+        .await
 }
 
 async fn run_omniscient_solver(
-    ros: std::sync::Arc<ROSInterface>,
+    solver: &Solver<ROSInterface>,
     algorithm: PathfindingAlgorithm,
-    delay: u64,
+    heuristic: Option<pathfinding::Heuristic>,
+    straight_bias: bool,
+    neighbor_order: Option<pathfinding::NeighborOrder>,
+    config: OmniscientConfig,
+    exports: &Exports<'_>,
 ) -> Result<()> {
-    info!("solving with {}", algorithm.name());
-    if delay > 0 {
-        debug!("delay: {}ms", delay);
+    match heuristic {
+        Some(heuristic) => info!(
+            "solving with {} ({:?} heuristic)",
+            algorithm.name(),
+            heuristic
+        ),
+        None => info!("solving with {}", algorithm.name()),
+    }
+    if !config.delay.is_zero() {
+        debug!("delay: {:?}", config.delay);
+    }
+
+    if straight_bias {
+        debug!("preferring straight moves among equally short paths");
     }
 
-    let result = solve_omniscient(ros, algorithm, delay).await?;
+    let result = if heuristic.is_some() || straight_bias {
+        let astar = pathfinding::AStar::new(heuristic.unwrap_or_default())
+            .with_straight_bias(straight_bias);
+        solver.solve_omniscient(astar, config).await?
+    } else if let Some(order) = neighbor_order {
+        debug!("expanding neighbors in {:?} order", order);
+        solver
+            .solve_omniscient(pathfinding::DFS::new(order), config)
+            .await?
+    } else {
+        solve_omniscient(solver, algorithm, config).await?
+    };
     print_result(&result);
-    Ok(())
+    print_interaction_stats(solver.backend().stats());
+    exports.write(&result)
 }
 
-async fn run_omniscient_benchmark(ros: std::sync::Arc<ROSInterface>, delay: u64) -> Result<()> {
+async fn run_omniscient_benchmark(
+    solver: &Solver<ROSInterface>,
+    config: OmniscientConfig,
+    filter: &BenchmarkFilter,
+    options: &BenchmarkOptions<'_>,
+) -> Result<()> {
     info!("benchmarking omniscient algorithms");
 
     let mut completed_results = Vec::new();
+    let mut failed_results = Vec::new();
+    let algorithms = benchmark_order(
+        PathfindingAlgorithm::all()
+            .enumerate()
+            .filter(|(_, algorithm)| filter.allows_pathfinding(*algorithm)),
+        options.shuffle_seed,
+    );
+    if algorithms.is_empty() {
+        eyre::bail!("no algorithms left to benchmark after --only/--skip");
+    }
 
-    for (i, algorithm) in PathfindingAlgorithm::all().enumerate() {
+    let mut run_stats = HashMap::new();
+
+    for (i, (canonical_idx, algorithm)) in algorithms.into_iter().enumerate() {
         info!("testing {}", algorithm.name());
 
-        if i > 0 {
+        match solve_repeatedly(solver, options.runs, i > 0, !config.plan_only, || {
+            solve_omniscient(solver, algorithm, config.clone())
+        })
+        .await
+        {
+            Ok((result, stats)) => {
+                run_stats.insert(algorithm.name().to_string(), stats);
+                completed_results.push((canonical_idx, algorithm.name().to_string(), result));
+            }
+            Err(e) if options.fail_fast => {
+                return Err(e.wrap_err(format!("{} failed", algorithm.name())));
+            }
+            Err(e) => {
+                log::error!("{} failed: {}", algorithm.name(), e);
+                failed_results.push((canonical_idx, algorithm.name().to_string(), e.to_string()));
+            }
+        }
+    }
+
+    export_report(
+        options.report,
+        "omniscient",
+        &completed_results,
+        &failed_results,
+    )?;
+    let results = canonical_results(completed_results);
+    print_benchmark_summary(&results, &run_stats, options.direction_counts);
+    export_benchmark_results(options, &results)?;
+    print_interaction_stats(solver.backend().stats());
+    Ok(())
+}
+
+/// fetches the map once for modes that plan several times on the same layout
+async fn fetch_maze(
+    solver: &Solver<ROSInterface>,
+    config: &OmniscientConfig,
+) -> Result<(BoundedMaze, Position, Position)> {
+    let map_response = solver.backend().get_ready_map(config.map_timeout).await?;
+    let parse = if config.strict_map {
+        BoundedMaze::from_flattened_strict
+    } else {
+        BoundedMaze::from_flattened_limited
+    };
+    let maze = parse(
+        map_response.occupancy_grid_flattened,
+        map_response.occupancy_grid_shape,
+        config.max_cells,
+    )?;
+    let start = maze
+        .find_robot()
+        .ok_or_else(|| eyre::eyre!("robot not found in maze"))?;
+    let target = maze
+        .find_target()
+        .ok_or_else(|| eyre::eyre!("target not found in maze"))?;
+
+    Ok((maze, start, target))
+}
+
+/// runs weighted A* once per epsilon on the same maze, comparing each path to the optimal one
+async fn run_epsilon_benchmark(
+    solver: &Solver<ROSInterface>,
+    config: OmniscientConfig,
+    epsilons: &[f64],
+    options: &BenchmarkOptions<'_>,
+) -> Result<()> {
+    use pathfinding::PathfindingAlgorithm as _;
+
+    if let Some(epsilon) = epsilons.iter().find(|e| e.is_nan() || **e < 1.0) {
+        eyre::bail!("epsilon must be at least 1.0, got {}", epsilon);
+    }
+
+    info!("sweeping weighted A* over {} epsilons", epsilons.len());
+
+    let (maze, start, target) = fetch_maze(solver, &config).await?;
+    let optimal = pathfinding::Dijkstra
+        .find_path(&maze, start, target)
+        .ok_or_else(|| eyre::eyre!("no path found"))?
+        .len();
+    debug!("optimal path: {} steps", optimal);
+
+    let mut completed_results = Vec::new();
+    let mut failed_results = Vec::new();
+    let runs = benchmark_order(epsilons.iter().copied().enumerate(), options.shuffle_seed);
+
+    for (i, (canonical_idx, epsilon)) in runs.into_iter().enumerate() {
+        info!("testing epsilon {}", epsilon);
+
+        if i > 0 && !config.plan_only {
             debug!("resetting maze");
-            ros.reset(false, String::new()).await?;
+            solver.backend().reset().await?;
             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         }
 
-        match solve_omniscient(ros.clone(), algorithm, delay).await {
+        match solver
+            .solve_omniscient(pathfinding::WeightedAStar::new(epsilon), config.clone())
+            .await
+        {
             Ok(result) => {
                 print_result(&result);
-                completed_results.push((algorithm.name().to_string(), result));
+                completed_results.push((canonical_idx, format!("{}", epsilon), result));
+            }
+            Err(e) if options.fail_fast => {
+                return Err(e.wrap_err(format!("epsilon {} failed", epsilon)));
             }
             Err(e) => {
-                log::error!("{} failed: {}", algorithm.name(), e);
+                log::error!("epsilon {} failed: {}", epsilon, e);
+                failed_results.push((canonical_idx, format!("{}", epsilon), e.to_string()));
             }
         }
     }
 
-    print_benchmark_summary(&completed_results);
+    export_report(
+        options.report,
+        "omniscient-epsilon",
+        &completed_results,
+        &failed_results,
+    )?;
+
+    let results = canonical_results(completed_results);
+    print_epsilon_summary(&results, optimal);
+    export_benchmark_results(options, &results)?;
+    print_interaction_stats(solver.backend().stats());
+    Ok(())
+}
+
+// ========== Offline Preview ==========
+
+/// loads a maze file and finds its robot and target, rejecting targets the robot cannot reach
+fn load_maze_file(path: &Path) -> Result<(BoundedMaze, Position, Position)> {
+    let maze = BoundedMaze::from_file(path)?;
+
+    let start = maze
+        .find_robot()
+        .ok_or_else(|| eyre::eyre!("{}: no robot ('r') in maze", path.display()))?;
+    let target = maze
+        .find_target()
+        .ok_or_else(|| eyre::eyre!("{}: no target ('t') in maze", path.display()))?;
+
+    if !maze.reachable_from(start).contains(&target) {
+        eyre::bail!(
+            "{}: target at {} is unreachable from {}",
+            path.display(),
+            target,
+            start
+        );
+    }
+
+    Ok((maze, start, target))
+}
+
+fn preview_file(path: &Path, algorithm: PathfindingAlgorithm, exports: &Exports<'_>) -> Result<()> {
+    let (maze, start, target) = load_maze_file(path)?;
+
+    info!(
+        "previewing {} on {}x{} maze from {}",
+        algorithm.name(),
+        maze.height(),
+        maze.width(),
+        path.display()
+    );
+
+    let result = plan_preview(&maze, start, target, pathfinder(algorithm)?)?;
+
+    println!("{}", maze.render(&result.waypoints));
+    println!(
+        "path: {}",
+        result
+            .path
+            .iter()
+            .map(|direction| direction.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    print_result(&result);
+    exports.write(&result)
+}
+
+/// prints the `k` cheapest distinct routes through a maze file, one per line
+fn list_paths(path: &Path, k: usize) -> Result<()> {
+    let (maze, start, target) = load_maze_file(path)?;
+
+    let paths = pathfinding::k_shortest_paths(&maze, start, target, k);
+    if paths.is_empty() {
+        eyre::bail!("no path found");
+    }
+    if paths.len() < k {
+        info!("only {} distinct paths exist", paths.len());
+    }
+
+    for (idx, moves) in paths.iter().enumerate() {
+        let cost: usize = pathfinding::path_to_waypoints(start, moves)
+            .into_iter()
+            .skip(1)
+            .map(|pos| maze.cost(pos))
+            .sum();
+        println!(
+            "{}: {} steps (cost {}): {}",
+            idx + 1,
+            moves.len(),
+            cost,
+            moves
+                .iter()
+                .map(|direction| direction.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+    }
     Ok(())
 }
 
+fn plan_preview(
+    maze: &BoundedMaze,
+    start: Position,
+    target: Position,
+    algorithm: impl pathfinding::PathfindingAlgorithm,
+) -> Result<pathfinding::PathResult> {
+    let planning_start = std::time::Instant::now();
+    let outcome = algorithm.search(maze, start, target);
+    let planning_time = planning_start.elapsed();
+    let path = outcome.path.ok_or_else(|| eyre::eyre!("no path found"))?;
+
+    Ok(
+        pathfinding::PathResult::new(path.len(), planning_time, std::time::Duration::default())
+            .with_path(maze, start, &path)
+            .with_nodes(outcome.nodes_expanded),
+    )
+}
+
+/// plans once on every maze in `dir` and writes one csv row per maze, smallest first
+fn run_scaling(algorithm: PathfindingAlgorithm, dir: &Path, output: Option<&Path>) -> Result<()> {
+    use pathfinding::PathfindingAlgorithm as _;
+
+    let pathfinder = pathfinder(algorithm)?;
+    let mut files: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+
+    info!(
+        "measuring {} on {} maze files from {}",
+        algorithm.name(),
+        files.len(),
+        dir.display()
+    );
+
+    let mut rows = Vec::new();
+    for file in &files {
+        let (maze, start, target) = match load_maze_file(file) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                log::warn!("skipping {}: {}", file.display(), e);
+                continue;
+            }
+        };
+
+        let planning_start = std::time::Instant::now();
+        let outcome = pathfinder.search(&maze, start, target);
+        let planning_time = planning_start.elapsed();
+
+        debug!(
+            "{}: {} cells, {} nodes expanded in {}",
+            file.display(),
+            maze.height() * maze.width(),
+            outcome.nodes_expanded,
+            Elapsed(planning_time)
+        );
+        rows.push((
+            maze.height() * maze.width(),
+            file.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            planning_time,
+            outcome,
+        ));
+    }
+
+    if rows.is_empty() {
+        eyre::bail!("no loadable maze files in {}", dir.display());
+    }
+    rows.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+
+    let mut csv = String::from("map,cells,planning_ns,nodes_expanded,path_length\n");
+    for (cells, name, planning_time, outcome) in &rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            name,
+            cells,
+            planning_time.as_nanos(),
+            outcome.nodes_expanded,
+            outcome
+                .path
+                .as_ref()
+                .map_or_else(String::new, |path| path.len().to_string())
+        ));
+    }
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, csv)?;
+            info!("wrote {} rows to {}", rows.len(), path.display());
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+fn diff_reports(old_path: &Path, new_path: &Path) -> Result<()> {
+    use report::{Change, Verdict};
+
+    let old = BenchmarkReport::load(old_path)?;
+    let new = BenchmarkReport::load(new_path)?;
+    if old.mode != new.mode {
+        log::warn!(
+            "comparing a {} report against a {} report",
+            old.mode,
+            new.mode
+        );
+    }
+
+    let diffs = old.diff(&new);
+
+    info!("");
+    info!("{} → {}:", old_path.display(), new_path.display());
+    info!("{:<50} {:>10}  {:>14}", "algorithm", "Δ steps", "Δ total");
+    info!("{:-<90}", "");
+
+    for diff in &diffs {
+        let line = match diff.change {
+            Change::Compared { steps, total_us } => {
+                let time = std::time::Duration::from_micros(total_us.unsigned_abs());
+                format!(
+                    "{:<50} {:>+10}  {:>14}",
+                    diff.algorithm,
+                    steps,
+                    format!("{}{}", if total_us < 0 { "-" } else { "+" }, Elapsed(time))
+                )
+            }
+            Change::NowFailing => format!("{:<50} now failing", diff.algorithm),
+            Change::NowPassing => format!("{:<50} now passing", diff.algorithm),
+            Change::StillFailing => format!("{:<50} still failing", diff.algorithm),
+            Change::Added => format!("{:<50} added", diff.algorithm),
+            Change::Removed => format!("{:<50} removed", diff.algorithm),
+        };
+        info!(
+            algorithm = diff.algorithm.as_str(),
+            verdict:? = diff.change.verdict();
+            "{}",
+            paint_verdict(line, diff.change.verdict())
+        );
+    }
+
+    let count = |verdict| {
+        diffs
+            .iter()
+            .filter(|diff| diff.change.verdict() == verdict)
+            .count()
+    };
+    info!("");
+    info!(
+        "{} regressions, {} improvements",
+        count(Verdict::Regression),
+        count(Verdict::Improvement)
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "structured-logs"))]
+fn paint_verdict(line: String, verdict: report::Verdict) -> String {
+    use colored::Colorize;
+
+    match verdict {
+        report::Verdict::Regression => line.red().to_string(),
+        report::Verdict::Improvement => line.green().to_string(),
+        report::Verdict::Unchanged => line,
+    }
+}
+
+/// structured logs carry the verdict as a field instead of color
+#[cfg(feature = "structured-logs")]
+fn paint_verdict(line: String, _verdict: report::Verdict) -> String {
+    line
+}
+
 // ========== Blind Solvers ==========
 
 async fn solve_blind(
-    ros: std::sync::Arc<ROSInterface>,
+    solver: &Solver<ROSInterface>,
     exploration: ExplorationAlgorithm,
     pathfinding: PathfindingAlgorithm,
-    delay: u64,
+    config: BlindConfig,
+    explorer_options: ExplorerOptions,
 ) -> Result<pathfinding::PathResult> {
-    match (exploration, pathfinding) {
-        (ExplorationAlgorithm::WallFollower, PathfindingAlgorithm::AStar) => {
-            let mut solver =
-                BlindSolver::new(exploration::WallFollower::new(), pathfinding::AStar, delay);
-            solver.solve(ros).await
-        }
-        (ExplorationAlgorithm::WallFollower, PathfindingAlgorithm::Dijkstra) => {
-            let mut solver = BlindSolver::new(
-                exploration::WallFollower::new(),
-                pathfinding::Dijkstra,
-                delay,
-            );
-            solver.solve(ros).await
-        }
-        (ExplorationAlgorithm::WallFollower, PathfindingAlgorithm::DFS) => {
-            let mut solver =
-                BlindSolver::new(exploration::WallFollower::new(), pathfinding::DFS, delay);
-            solver.solve(ros).await
-        }
-        (ExplorationAlgorithm::RecursiveBacktracker, PathfindingAlgorithm::AStar) => {
-            let mut solver = BlindSolver::new(
-                exploration::RecursiveBacktracker::new(),
-                pathfinding::AStar,
-                delay,
-            );
-            solver.solve(ros).await
-        }
-        (ExplorationAlgorithm::RecursiveBacktracker, PathfindingAlgorithm::Dijkstra) => {
-            let mut solver = BlindSolver::new(
-                exploration::RecursiveBacktracker::new(),
-                pathfinding::Dijkstra,
-                delay,
-            );
-            solver.solve(ros).await
-        }
-        (ExplorationAlgorithm::RecursiveBacktracker, PathfindingAlgorithm::DFS) => {
-            let mut solver = BlindSolver::new(
-                exploration::RecursiveBacktracker::new(),
-                pathfinding::DFS,
-                delay,
-            );
-            solver.solve(ros).await
-        }
-    }
+    let explorer = registry::explorer_by_name(&value_name(exploration), explorer_options)
+        .ok_or_else(|| eyre::eyre!("{} is not registered", exploration.name()))?;
+
+    solver
+        .solve_blind(explorer, pathfinder(pathfinding)?, config)
+        .await
 }
 
 async fn run_blind_solver(
-    ros: std::sync::Arc<ROSInterface>,
+    solver: &Solver<ROSInterface>,
     exploration: ExplorationAlgorithm,
     pathfinding: PathfindingAlgorithm,
-    delay: u64,
+    config: BlindConfig,
+    explorer_options: ExplorerOptions,
+    repeat: u64,
+    exports: &Exports<'_>,
 ) -> Result<()> {
     info!(
         "exploring with {} + {}",
         exploration.name(),
         pathfinding.name()
     );
-    if delay > 0 {
-        debug!("delay: {}ms", delay);
+    if !config.delay.is_zero() {
+        debug!("delay: {:?}", config.delay);
     }
 
-    let result = solve_blind(ros, exploration, pathfinding, delay).await?;
-    print_result(&result);
-    Ok(())
+    let mut run_steps = Vec::new();
+    let mut last_result = None;
+
+    for run in 0..repeat {
+        if run > 0 {
+            debug!("resetting maze");
+            solver.backend().reset().await?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+        if repeat > 1 {
+            info!("run {}/{}", run + 1, repeat);
+        }
+
+        let result = solve_blind(
+            solver,
+            exploration,
+            pathfinding,
+            config.clone(),
+            explorer_options,
+        )
+        .await?;
+        print_result(&result);
+        run_steps.push(result.steps);
+        last_result = Some(result);
+    }
+
+    if repeat > 1 {
+        print_repeat_summary(&run_steps);
+    }
+    print_interaction_stats(solver.backend().stats());
+
+    // exports describe the last run
+    match last_result {
+        Some(result) => exports.write(&result),
+        None => Ok(()),
+    }
 }
 
-async fn run_blind_benchmark(ros: std::sync::Arc<ROSInterface>, delay: u64) -> Result<()> {
+async fn run_blind_benchmark(
+    solver: &Solver<ROSInterface>,
+    config: BlindConfig,
+    explorer_options: ExplorerOptions,
+    filter: &BenchmarkFilter,
+    options: &BenchmarkOptions<'_>,
+) -> Result<()> {
     info!("benchmarking blind algorithms");
 
     let mut completed_results = Vec::new();
-    let mut test_num = 0;
+    let mut failed_results = Vec::new();
+    let combinations = benchmark_order(
+        ExplorationAlgorithm::all()
+            .flat_map(|exploration| {
+                PathfindingAlgorithm::all().map(move |pathfinding| (exploration, pathfinding))
+            })
+            .enumerate()
+            .filter(|(_, (exploration, pathfinding))| {
+                filter.allows_exploration(*exploration) && filter.allows_pathfinding(*pathfinding)
+            }),
+        options.shuffle_seed,
+    );
+    if combinations.is_empty() {
+        eyre::bail!("no combinations left to benchmark after --only/--skip");
+    }
 
-    for exploration in ExplorationAlgorithm::all() {
-        for pathfinding in PathfindingAlgorithm::all() {
-            let name = format!("{} + {}", exploration.name(), pathfinding.name());
-            info!("testing {}", name);
+    let mut run_stats = HashMap::new();
 
-            if test_num > 0 {
-                debug!("resetting maze");
-                ros.reset(false, String::new()).await?;
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-            }
+    for (test_num, (canonical_idx, (exploration, pathfinding))) in
+        combinations.into_iter().enumerate()
+    {
+        let name = format!("{} + {}", exploration.name(), pathfinding.name());
+        info!("testing {}", name);
 
-            match solve_blind(ros.clone(), exploration, pathfinding, delay).await {
-                Ok(result) => {
-                    print_result(&result);
-                    completed_results.push((name, result));
-                }
-                Err(e) => {
-                    log::error!("{} failed: {}", name, e);
-                }
+        match solve_repeatedly(solver, options.runs, test_num > 0, true, || {
+            solve_blind(
+                solver,
+                exploration,
+                pathfinding,
+                config.clone(),
+                explorer_options,
+            )
+        })
+        .await
+        {
+            Ok((result, stats)) => {
+                run_stats.insert(name.clone(), stats);
+                completed_results.push((canonical_idx, name, result));
             }
+            Err(e) if options.fail_fast => return Err(e.wrap_err(format!("{} failed", name))),
+            Err(e) => {
+                log::error!("{} failed: {}", name, e);
+                failed_results.push((canonical_idx, name, e.to_string()));
+            }
+        }
+    }
 
-            test_num += 1;
+    export_report(options.report, "blind", &completed_results, &failed_results)?;
+
+    let results = canonical_results(completed_results);
+    print_benchmark_summary(&results, &run_stats, options.direction_counts);
+    export_benchmark_results(options, &results)?;
+    print_interaction_stats(solver.backend().stats());
+    Ok(())
+}
+
+// ========== Head-to-head ==========
+
+/// solves the same maze once per algorithm, resetting in between
+async fn run_compare(
+    solver: &Solver<ROSInterface>,
+    algorithms: [AlgorithmName; 2],
+    pathfinding: PathfindingAlgorithm,
+    same_map: bool,
+    omniscient_config: OmniscientConfig,
+    blind_config: BlindConfig,
+    explorer_options: ExplorerOptions,
+) -> Result<()> {
+    if same_map && let [AlgorithmName::Pathfinding(a), AlgorithmName::Pathfinding(b)] = algorithms {
+        return compare_plans(solver, [a, b], &omniscient_config).await;
+    }
+
+    let mut results = Vec::new();
+
+    for (i, algorithm) in algorithms.into_iter().enumerate() {
+        if i > 0 {
+            debug!("resetting maze");
+            solver.backend().reset().await?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         }
+
+        let (name, result) = match algorithm {
+            AlgorithmName::Pathfinding(algorithm) => {
+                info!("solving with {}", algorithm.name());
+                let result = solve_omniscient(solver, algorithm, omniscient_config.clone()).await?;
+                (algorithm.name().to_string(), result)
+            }
+            AlgorithmName::Exploration(exploration) => {
+                let name = format!("{} + {}", exploration.name(), pathfinding.name());
+                info!("exploring with {}", name);
+                let result = solve_blind(
+                    solver,
+                    exploration,
+                    pathfinding,
+                    blind_config.clone(),
+                    explorer_options,
+                )
+                .await?;
+                (name, result)
+            }
+        };
+
+        print_result(&result);
+        results.push((name, result));
     }
 
-    print_benchmark_summary(&completed_results);
+    print_comparison(&results[0], &results[1]);
+    print_interaction_stats(solver.backend().stats());
     Ok(())
 }
 
+/// plans with both pathfinders on one fetched map, so any difference is purely algorithmic
+async fn compare_plans(
+    solver: &Solver<ROSInterface>,
+    algorithms: [PathfindingAlgorithm; 2],
+    config: &OmniscientConfig,
+) -> Result<()> {
+    let (maze, start, target) = fetch_maze(solver, config).await?;
+    info!(
+        "planning on one {}x{} map: {} → {}",
+        maze.height(),
+        maze.width(),
+        start,
+        target
+    );
+
+    let results = plan_each(&maze, start, target, algorithms)?;
+    print_comparison(&results[0], &results[1]);
+    print_interaction_stats(solver.backend().stats());
+    Ok(())
+}
+
+/// plans with every algorithm on the same maze, in order
+fn plan_each(
+    maze: &BoundedMaze,
+    start: Position,
+    target: Position,
+    algorithms: [PathfindingAlgorithm; 2],
+) -> Result<Vec<(String, pathfinding::PathResult)>> {
+    let mut results = Vec::new();
+    for algorithm in algorithms {
+        info!("planning with {}", algorithm.name());
+        let result = plan_preview(maze, start, target, pathfinder(algorithm)?)?;
+        print_result(&result);
+        results.push((algorithm.name().to_string(), result));
+    }
+    Ok(results)
+}
+
+fn print_comparison(a: &(String, pathfinding::PathResult), b: &(String, pathfinding::PathResult)) {
+    info!("");
+    info!("comparison:");
+    info!(
+        "{:<50} {:>8}  {:>8}  {:>12}  {:>12}",
+        "algorithm", "steps", "expanded", "plan", "total"
+    );
+    info!("{:-<100}", "");
+
+    for (name, result) in [a, b] {
+        info!(
+            "{:<50} {:>8}  {:>8}  {:>12}  {:>12}",
+            name,
+            result.steps,
+            result.nodes_expanded,
+            Elapsed(result.planning_time),
+            Elapsed(result.total_time),
+        );
+    }
+
+    let ((a_name, a_result), (b_name, b_result)) = (a, b);
+    let step_delta = b_result.steps as i64 - a_result.steps as i64;
+    let time_delta = if b_result.total_time >= a_result.total_time {
+        format!("+{}", Elapsed(b_result.total_time - a_result.total_time))
+    } else {
+        format!("-{}", Elapsed(a_result.total_time - b_result.total_time))
+    };
+
+    info!("");
+    info!("delta (b - a): {:+} steps, {}", step_delta, time_delta);
+
+    // fewer steps wins, total time breaks ties
+    let winner = match a_result
+        .steps
+        .cmp(&b_result.steps)
+        .then(a_result.total_time.cmp(&b_result.total_time))
+    {
+        std::cmp::Ordering::Less => Some(a_name),
+        std::cmp::Ordering::Greater => Some(b_name),
+        std::cmp::Ordering::Equal => None,
+    };
+
+    match winner {
+        Some(name) => info!("winner: {}", name),
+        None => info!("tie"),
+    }
+}
+
 // ========== Utilities ==========
 
+fn pathfinder(
+    algorithm: PathfindingAlgorithm,
+) -> Result<Box<dyn pathfinding::PathfindingAlgorithm>> {
+    registry::pathfinder_by_name(&value_name(algorithm))
+        .ok_or_else(|| eyre::eyre!("{} is not registered", algorithm.name()))
+}
+
+/// the name clap parsed the algorithm from, which is what the registry is keyed by
+fn value_name(algorithm: impl ValueEnum) -> String {
+    algorithm
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// benchmark iteration order, shuffled deterministically when a seed is given
+fn benchmark_order<T>(items: impl Iterator<Item = T>, shuffle_seed: Option<u64>) -> Vec<T> {
+    use rand::{SeedableRng, rngs::SmallRng, seq::SliceRandom};
+
+    let mut items: Vec<T> = items.collect();
+    if let Some(seed) = shuffle_seed {
+        info!("shuffling benchmark order (seed {})", seed);
+        items.shuffle(&mut SmallRng::seed_from_u64(seed));
+    }
+    items
+}
+
+/// restores canonical order (the index each run had before shuffling) for the summary
+fn canonical_results(
+    mut results: Vec<(usize, String, pathfinding::PathResult)>,
+) -> Vec<(String, pathfinding::PathResult)> {
+    results.sort_by_key(|(canonical_idx, _, _)| *canonical_idx);
+    results
+        .into_iter()
+        .map(|(_, name, result)| (name, result))
+        .collect()
+}
+
+/// solves `runs` times, resetting before each solve except the sweep's very first (or never
+/// when `reset` is off), and returns the last run's result with the spread over all of them;
+/// the first failing run fails the lot
+async fn solve_repeatedly<F, Fut>(
+    solver: &Solver<ROSInterface>,
+    runs: usize,
+    reset_first: bool,
+    reset: bool,
+    mut solve: F,
+) -> Result<(pathfinding::PathResult, BenchmarkStats)>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<pathfinding::PathResult>>,
+{
+    let mut results = Vec::with_capacity(runs);
+
+    for run in 0..runs {
+        if reset && (reset_first || run > 0) {
+            debug!("resetting maze");
+            solver.backend().reset().await?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+        if runs > 1 {
+            info!("run {}/{}", run + 1, runs);
+        }
+
+        let result = solve().await?;
+        print_result(&result);
+        results.push(result);
+    }
+
+    let stats = BenchmarkStats::from_results(&results)
+        .ok_or_else(|| eyre::eyre!("benchmark needs at least one run"))?;
+    let last = results.pop().expect("stats exist, so there is a run");
+    Ok((last, stats))
+}
+
+fn random_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
 fn print_result(result: &pathfinding::PathResult) {
     info!(
-        "finished in {} steps ({:?})",
-        result.steps, result.total_time
+        "finished in {} steps ({})",
+        result.steps,
+        Elapsed(result.total_time)
+    );
+    debug!("planning: {}", Elapsed(result.planning_time));
+    debug!("execution: {}", Elapsed(result.execution_time));
+}
+
+fn export_report(
+    path: Option<&Path>,
+    mode: &str,
+    completed: &[(usize, String, pathfinding::PathResult)],
+    failed: &[(usize, String, String)],
+) -> Result<()> {
+    if let Some(path) = path {
+        BenchmarkReport::new(mode, completed, failed).save(path)?;
+        info!(
+            "wrote report with {} runs ({} failed) to {}",
+            completed.len() + failed.len(),
+            failed.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn export_benchmark_results(
+    options: &BenchmarkOptions<'_>,
+    results: &[(String, pathfinding::PathResult)],
+) -> Result<()> {
+    if let Some(format) = options.output {
+        let records: Vec<_> = results
+            .iter()
+            .map(|(name, result)| BenchmarkRecord::new(name, result))
+            .collect();
+        write_benchmark_results(&records, format, options.output_file)?;
+        if let Some(path) = options.output_file {
+            info!(
+                "wrote {} benchmark records to {}",
+                records.len(),
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_interaction_stats(stats: InteractionStats) {
+    info!(
+        get_map_calls = stats.get_map_calls,
+        move_calls = stats.move_calls,
+        reset_calls = stats.reset_calls,
+        sensor_frames = stats.sensor_frames;
+        "simulator calls: {} get_map, {} move, {} reset; {} sensor frames received",
+        stats.get_map_calls,
+        stats.move_calls,
+        stats.reset_calls,
+        stats.sensor_frames
     );
-    debug!("planning: {:?}", result.planning_time);
-    debug!("execution: {:?}", result.execution_time);
 }
 
-fn print_benchmark_summary(results: &[(String, pathfinding::PathResult)]) {
+/// sweep-wide benchmark settings shared by every mode
+struct BenchmarkOptions<'a> {
+    shuffle_seed: Option<u64>,
+    direction_counts: bool,
+    report: Option<&'a Path>,
+    /// solves per algorithm, resetting in between
+    runs: usize,
+    output: Option<BenchmarkFormat>,
+    /// stdout when unset
+    output_file: Option<&'a Path>,
+    /// return the first algorithm error instead of logging it and moving on
+    fail_fast: bool,
+}
+
+/// where a single solve writes its artifacts
+struct Exports<'a> {
+    waypoints: Option<&'a Path>,
+    record_trace: Option<&'a Path>,
+    run: Option<&'a Path>,
+    gif: Option<&'a Path>,
+    gif_frame_delay: u32,
+}
+
+impl Exports<'_> {
+    fn write(&self, result: &pathfinding::PathResult) -> Result<()> {
+        export_waypoints(self.waypoints, result)?;
+        export_trace(self.record_trace, result)?;
+        export_run(self.run, result)?;
+        export_gif(self.gif, self.gif_frame_delay, result)
+    }
+}
+
+fn print_repeat_summary(run_steps: &[usize]) {
+    let Some(stats) = StepStats::from_steps(run_steps) else {
+        return;
+    };
+
+    info!("");
+    info!(
+        runs = run_steps.len(),
+        mean_steps = stats.mean,
+        median_steps = stats.median,
+        stddev_steps = stats.stddev,
+        min_steps = stats.min,
+        max_steps = stats.max;
+        "{} runs: mean {:.1} ± {:.1} steps, median {:.1}, min {}, max {}",
+        run_steps.len(),
+        stats.mean,
+        stats.stddev,
+        stats.median,
+        stats.min,
+        stats.max
+    );
+}
+
+fn export_waypoints(path: Option<&Path>, result: &pathfinding::PathResult) -> Result<()> {
+    if let Some(path) = path {
+        pathfinding::write_waypoints_csv(path, &result.waypoints)?;
+        info!(
+            "wrote {} waypoints to {}",
+            result.waypoints.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn export_trace(path: Option<&Path>, result: &pathfinding::PathResult) -> Result<()> {
+    if let Some(path) = path {
+        let trace = TraceFile::from_result(result);
+        trace.save(path)?;
+        info!(
+            "recorded {} trace steps to {}",
+            trace.steps.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn export_run(path: Option<&Path>, result: &pathfinding::PathResult) -> Result<()> {
+    if let Some(path) = path {
+        RunExport::from_result(result).save(path)?;
+        info!("wrote run summary to {}", path.display());
+    }
+    Ok(())
+}
+
+fn export_gif(
+    path: Option<&Path>,
+    frame_delay: u32,
+    result: &pathfinding::PathResult,
+) -> Result<()> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    let (Some(maze), Some(&start)) = (&result.maze, result.waypoints.first()) else {
+        log::warn!("no planned path to animate, skipping {}", path.display());
+        return Ok(());
+    };
+
+    let frames = gif::export_gif(maze, start, &result.path, path, frame_delay)?;
+    info!("wrote {} gif frames to {}", frames, path.display());
+    Ok(())
+}
+
+fn print_epsilon_summary(results: &[(String, pathfinding::PathResult)], optimal: usize) {
+    info!("");
+    info!("epsilon sweep results (optimal path: {} steps):", optimal);
+    info!(
+        "{:<10} {:>8}  {:>8}  {:>12}  {:>12}",
+        "epsilon", "path", "ratio", "plan", "total"
+    );
+    info!("{:-<58}", "");
+
+    for (epsilon, result) in results {
+        let ratio = result.path.len() as f64 / optimal.max(1) as f64;
+        info!(
+            epsilon = epsilon.as_str(),
+            path = result.path.len(),
+            optimality_ratio = ratio,
+            planning_us = result.planning_time.as_micros() as u64,
+            total_us = result.total_time.as_micros() as u64;
+            "{:<10} {:>8}  {:>8.3}  {:>12}  {:>12}",
+            epsilon,
+            result.path.len(),
+            ratio,
+            Elapsed(result.planning_time),
+            Elapsed(result.total_time),
+        );
+    }
+}
+
+/// with more than one run per algorithm, steps and total time show the mean ± standard deviation
+/// while the other columns describe the last run
+fn print_benchmark_summary(
+    results: &[(String, pathfinding::PathResult)],
+    run_stats: &HashMap<String, BenchmarkStats>,
+    direction_counts: bool,
+) {
+    let repeated = run_stats.values().any(|stats| stats.runs > 1);
+    let (steps_width, total_width) = if repeated { (16, 24) } else { (8, 12) };
+
     info!("");
     info!("benchmark results:");
     info!(
-        "{:<50} {:>8}  {:>12}  {:>12}",
-        "algorithm", "steps", "plan", "total"
+        "{:<50} {:>steps_width$}  {:>8}  {:>12}  {:>total_width$}",
+        "algorithm", "steps", "expanded", "plan", "total"
     );
-    info!("{:-<90}", "");
+    info!("{:-<1$}", "", 76 + steps_width + total_width);
 
     for (name, result) in results {
+        let (steps, total) = match run_stats.get(name) {
+            Some(stats) if repeated => (
+                format!("{:.1} ± {:.1}", stats.mean_steps, stats.stddev_steps),
+                format!(
+                    "{} ± {}",
+                    Elapsed(stats.mean_total),
+                    Elapsed(stats.stddev_total)
+                ),
+            ),
+            _ => (
+                result.steps.to_string(),
+                Elapsed(result.total_time).to_string(),
+            ),
+        };
+        let stddev_steps = run_stats.get(name).map_or(0.0, |stats| stats.stddev_steps);
+
+        info!(
+            algorithm = name.as_str(),
+            steps = result.steps,
+            stddev_steps = stddev_steps,
+            nodes_expanded = result.nodes_expanded,
+            planning_us = result.planning_time.as_micros() as u64,
+            total_us = result.total_time.as_micros() as u64;
+            "{:<50} {:>steps_width$}  {:>8}  {:>12}  {:>total_width$}",
+            name,
+            steps,
+            result.nodes_expanded,
+            Elapsed(result.planning_time),
+            total,
+        );
+    }
+
+    if direction_counts {
+        info!("");
         info!(
-            "{:<50} {:>8}  {:>12?}  {:>12?}",
-            name, result.steps, result.planning_time, result.total_time,
+            "{:<50} {:>8} {:>8} {:>8} {:>8}",
+            "algorithm", "up", "down", "left", "right"
         );
+        info!("{:-<90}", "");
+
+        for (name, result) in results {
+            let [up, down, left, right] = result.direction_counts;
+            info!(
+                algorithm = name.as_str(),
+                up = up,
+                down = down,
+                left = left,
+                right = right;
+                "{:<50} {:>8} {:>8} {:>8} {:>8}",
+                name, up, down, left, right,
+            );
+        }
     }
 
     info!("");
-    if let Some((name, result)) = results.iter().min_by_key(|(_, r)| r.steps) {
-        info!("best: {} ({} steps)", name, result.steps);
+    // rank by the means when there are several runs, so one lucky run cannot win
+    let ranked: Vec<(&str, f64, std::time::Duration)> = results
+        .iter()
+        .map(|(name, result)| match run_stats.get(name) {
+            Some(stats) => (name.as_str(), stats.mean_steps, stats.mean_total),
+            None => (name.as_str(), result.steps as f64, result.total_time),
+        })
+        .collect();
+
+    if let Some((name, steps, _)) = ranked.iter().min_by(|a, b| a.1.total_cmp(&b.1)) {
+        info!("best: {} ({} steps)", name, steps);
     }
 
-    if let Some((name, result)) = results.iter().min_by_key(|(_, r)| r.total_time) {
-        info!("fastest: {} ({:?})", name, result.total_time);
+    if let Some((name, _, total)) = ranked.iter().min_by_key(|(_, _, total)| *total) {
+        info!("fastest: {} ({})", name, Elapsed(*total));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimal_pathfinders_tie_on_one_map() {
+        let maze = BoundedMaze::from_ascii("rfff\nfbbf\nffft\n").unwrap();
+        let (start, target) = (maze.find_robot().unwrap(), maze.find_target().unwrap());
+
+        let results = plan_each(
+            &maze,
+            start,
+            target,
+            [PathfindingAlgorithm::AStar, PathfindingAlgorithm::IDDFS],
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].1.steps, 5);
+        assert_eq!(results[1].1.steps, 5);
     }
 }
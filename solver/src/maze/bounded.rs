@@ -1,15 +1,39 @@
-use super::{cell::Cell, position::Position};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+
+use super::{cell::Cell, corner::CornerPolicy, position::Position};
 use crate::ros::types::MoveDirection;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Maze {
     grid: Vec<Cell>,
     width: usize,
     height: usize,
+    /// corner policy for diagonal moves, `None` keeps the maze 4-connected
+    diagonal: Option<CornerPolicy>,
+}
+
+/// largest grid accepted from external data unless a caller picks its own limit
+pub const DEFAULT_MAX_CELLS: usize = 1 << 20;
+
+fn count_unrecognized(flattened: &[String]) -> usize {
+    flattened
+        .iter()
+        .filter(|symbol| Cell::parse_symbol(symbol).is_none())
+        .count()
 }
 
 impl Maze {
     pub fn from_flattened(flattened: Vec<String>, shape: Vec<u8>) -> eyre::Result<Self> {
+        Self::from_flattened_limited(flattened, shape, DEFAULT_MAX_CELLS)
+    }
+
+    /// like `from_flattened`, rejecting grids over `max_cells` before any cell is parsed
+    pub fn from_flattened_limited(
+        flattened: Vec<String>,
+        shape: Vec<u8>,
+        max_cells: usize,
+    ) -> eyre::Result<Self> {
         if shape.len() != 2 {
             eyre::bail!("invalid shape: expected [height, width], got {:?}", shape);
         }
@@ -17,6 +41,18 @@ impl Maze {
         let height = shape[0] as usize;
         let width = shape[1] as usize;
 
+        // a u8 shape tops out at 255x255, so an oversized map can only come from the flattened
+        // grid itself; checking it first also catches one that disagrees with the shape
+        if flattened.len() > max_cells {
+            eyre::bail!(
+                "{}x{} maze with {} cells exceeds the limit of {}",
+                height,
+                width,
+                flattened.len(),
+                max_cells
+            );
+        }
+
         if flattened.len() != width * height {
             eyre::bail!(
                 "grid size mismatch: expected {}, got {}",
@@ -25,13 +61,178 @@ impl Maze {
             );
         }
 
-        let grid = flattened.iter().map(|s| Cell::from_str(s)).collect();
+        let unrecognized = count_unrecognized(&flattened);
+        if unrecognized > 0 {
+            log::warn!(
+                "{} of {} cells have unrecognized symbols and were read as unknown",
+                unrecognized,
+                flattened.len()
+            );
+        }
+
+        let grid = flattened.iter().map(|s| Cell::from_symbol(s)).collect();
 
-        Ok(Self {
+        let maze = Self {
             grid,
             width,
             height,
-        })
+            diagonal: None,
+        };
+
+        Ok(maze)
+    }
+
+    /// like `from_flattened_limited`, but an unrecognized cell symbol is an error instead of an
+    /// unknown cell, and so is a grid without exactly one robot or without a target
+    pub fn from_flattened_strict(
+        flattened: Vec<String>,
+        shape: Vec<u8>,
+        max_cells: usize,
+    ) -> eyre::Result<Self> {
+        if let Some((idx, symbol)) = flattened
+            .iter()
+            .enumerate()
+            .find(|(_, symbol)| Cell::parse_symbol(symbol).is_none())
+        {
+            eyre::bail!(
+                "unrecognized cell symbol {:?} at index {} ({} unrecognized in total)",
+                symbol,
+                idx,
+                count_unrecognized(&flattened)
+            );
+        }
+
+        let maze = Self::from_flattened_limited(flattened, shape, max_cells)?;
+        match maze
+            .grid
+            .iter()
+            .filter(|&&cell| cell == Cell::Robot)
+            .count()
+        {
+            0 => eyre::bail!("map has no robot cell"),
+            1 => {}
+            robots => eyre::bail!("map has {} robot cells, expected one", robots),
+        }
+        if maze.find_target().is_none() {
+            eyre::bail!("map has no target cell");
+        }
+        Ok(maze)
+    }
+
+    /// makes `neighbors` 8-connected, with `policy` deciding which diagonal steps may pass wall
+    /// corners; every pathfinder going through `neighbors` then plans diagonal moves, each
+    /// costing one step like a cardinal move
+    pub fn with_diagonal_moves(mut self, policy: CornerPolicy) -> Self {
+        self.diagonal = Some(policy);
+        self
+    }
+
+    pub fn allows_diagonal_moves(&self) -> bool {
+        self.diagonal.is_some()
+    }
+
+    /// fnv-1a over the dimensions, every cell and the diagonal policy; unlike `Hash` it is
+    /// stable across runs and builds, so it can name files on disk
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let diagonal = match self.diagonal {
+            None => "none",
+            Some(CornerPolicy::Forbid) => "forbid",
+            Some(CornerPolicy::AllowOneWall) => "allow-one-wall",
+            Some(CornerPolicy::AllowBoth) => "allow-both",
+        };
+
+        (self.height as u64)
+            .to_le_bytes()
+            .into_iter()
+            .chain((self.width as u64).to_le_bytes())
+            .chain(self.grid.iter().flat_map(|cell| cell.symbol().bytes()))
+            .chain(diagonal.bytes())
+            .fold(FNV_OFFSET, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// loads a maze grid from a text file in the format `from_ascii` parses
+    pub fn from_file(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_ascii(&contents).map_err(|e| eyre::eyre!("{}: {}", path.display(), e))
+    }
+
+    /// parses a maze grid, one row per line, cells either comma-separated (the culling_games
+    /// csv map format) or as consecutive characters; blank lines are skipped
+    pub fn from_ascii(contents: &str) -> eyre::Result<Self> {
+        let rows: Vec<Vec<String>> = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if line.contains(',') {
+                    line.split(',')
+                        .map(|cell| cell.trim().to_lowercase())
+                        .collect()
+                } else {
+                    line.chars()
+                        .map(|cell| cell.to_lowercase().to_string())
+                        .collect()
+                }
+            })
+            .collect();
+
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+
+        if let Some((idx, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            eyre::bail!("row {} has {} cells, expected {}", idx, row.len(), width);
+        }
+
+        if height > u8::MAX as usize || width > u8::MAX as usize {
+            eyre::bail!("{}x{} maze is too large", height, width);
+        }
+
+        Self::from_flattened(
+            rows.into_iter().flatten().collect(),
+            vec![height as u8, width as u8],
+        )
+    }
+
+    /// writes the grid in the comma-separated format `from_file` reads back
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        let mut out = String::with_capacity(self.grid.len() * 2);
+        for row in self.grid.chunks(self.width.max(1)) {
+            let symbols: Vec<&str> = row.iter().map(|cell| cell.symbol()).collect();
+            out.push_str(&symbols.join(","));
+            out.push('\n');
+        }
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// ascii rendering with `path` positions overlaid as `*`
+    pub fn render(&self, path: &[Position]) -> String {
+        let path: HashSet<Position> = path.iter().copied().collect();
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let pos = Position::new(row, col);
+                let symbol = match self.get(pos).unwrap_or(Cell::Unknown) {
+                    Cell::Robot => 'R',
+                    Cell::Target => 'T',
+                    _ if path.contains(&pos) => '*',
+                    Cell::Free => ' ',
+                    Cell::Slow => '~',
+                    Cell::Blocked => '#',
+                    Cell::Unknown => '?',
+                };
+                out.push(symbol);
+            }
+            out.push('\n');
+        }
+
+        out
     }
 
     pub fn width(&self) -> usize {
@@ -46,36 +247,292 @@ impl Maze {
         (self.height, self.width)
     }
 
+    /// the one validated accessor: `None` outside the maze's bounds, and never a panic even if
+    /// the grid were shorter than its dimensions claim
     pub fn get(&self, pos: Position) -> Option<Cell> {
-        if pos.row < self.height && pos.col < self.width {
-            Some(self.grid[pos.to_index(self.width)])
-        } else {
-            None
+        self.index(pos).and_then(|idx| self.grid.get(idx)).copied()
+    }
+
+    /// out-of-bounds positions are ignored
+    pub fn set(&mut self, pos: Position, cell: Cell) {
+        if let Some(slot) = self.index(pos).and_then(|idx| self.grid.get_mut(idx)) {
+            *slot = cell;
         }
     }
 
+    fn index(&self, pos: Position) -> Option<usize> {
+        (pos.row < self.height && pos.col < self.width).then(|| pos.to_index(self.width))
+    }
+
     pub fn is_walkable(&self, pos: Position) -> bool {
         self.get(pos).is_some_and(|cell| cell.is_walkable())
     }
 
-    pub fn find_robot(&self) -> Option<Position> {
+    /// cost of moving onto `pos`, 1 outside the maze so callers never see a zero
+    pub fn cost(&self, pos: Position) -> usize {
+        self.get(pos).map_or(1, Cell::cost)
+    }
+
+    /// cheapest cost of any walkable cell, what heuristics scale by to stay admissible
+    pub fn min_cost(&self) -> usize {
         self.grid
             .iter()
-            .position(|&cell| cell == Cell::Robot)
-            .map(|idx| Position::from_index(idx, self.width))
+            .filter(|cell| cell.is_walkable())
+            .map(|cell| cell.cost())
+            .min()
+            .unwrap_or(1)
+    }
+
+    /// cells that are not `Unknown`, i.e. every cell of a fetched map
+    pub fn known_cells(&self) -> usize {
+        self.grid
+            .iter()
+            .filter(|cell| **cell != Cell::Unknown)
+            .count()
+    }
+
+    pub fn find_robot(&self) -> Option<Position> {
+        self.find(Cell::Robot)
     }
 
     pub fn find_target(&self) -> Option<Position> {
+        self.find(Cell::Target)
+    }
+
+    /// first position holding `cell`, in row-major order
+    fn find(&self, cell: Cell) -> Option<Position> {
         self.grid
             .iter()
-            .position(|&cell| cell == Cell::Target)
+            .position(|&c| c == cell)
             .map(|idx| Position::from_index(idx, self.width))
     }
 
+    /// walkable neighbors, diagonal ones included only when enabled and allowed by the corner
+    /// policy
     pub fn neighbors(&self, pos: Position) -> Vec<(Position, MoveDirection)> {
-        pos.neighbors(self.bounds())
+        let mut neighbors: Vec<_> = pos
+            .neighbors(self.bounds(), false)
             .into_iter()
             .filter(|(p, _)| self.is_walkable(*p))
+            .collect();
+
+        if let Some(policy) = self.diagonal {
+            neighbors.extend(self.diagonal_neighbors(pos, policy));
+        }
+        neighbors
+    }
+
+    /// flood fill over walkable cells, returning every position reachable from `start`
+    pub fn reachable_from(&self, start: Position) -> HashSet<Position> {
+        let mut reachable = HashSet::new();
+        if !self.is_walkable(start) {
+            return reachable;
+        }
+
+        let mut queue = VecDeque::from([start]);
+        reachable.insert(start);
+
+        while let Some(current) = queue.pop_front() {
+            for (neighbor, _) in self.neighbors(current) {
+                if reachable.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// diagonal neighbors that are walkable and reachable under the given corner policy
+    pub fn diagonal_neighbors(
+        &self,
+        pos: Position,
+        policy: CornerPolicy,
+    ) -> Vec<(Position, MoveDirection)> {
+        MoveDirection::diagonals()
+            .into_iter()
+            .filter_map(|direction| {
+                let (dr, dc) = direction.offset();
+                let diagonal = pos
+                    .move_in_direction(direction, self.bounds())
+                    .filter(|p| self.is_walkable(*p))?;
+                let vertical = (pos + (dr, 0)).is_some_and(|p| self.is_walkable(p));
+                let horizontal = (pos + (0, dc)).is_some_and(|p| self.is_walkable(p));
+
+                policy
+                    .allows(vertical, horizontal)
+                    .then_some((diagonal, direction))
+            })
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasher, RandomState};
+
+    use super::*;
+
+    #[test]
+    fn reachable_from_excludes_a_walled_off_pocket() {
+        let maze = Maze::from_ascii("bbbbbbb\nbrfbffb\nbffbftb\nbbbbbbb\n").unwrap();
+        let start = maze.find_robot().unwrap();
+
+        let reachable = maze.reachable_from(start);
+
+        let expected: HashSet<Position> = [(1, 1), (1, 2), (2, 1), (2, 2)]
+            .into_iter()
+            .map(|(row, col)| Position::new(row, col))
+            .collect();
+        assert_eq!(reachable, expected);
+        assert!(!reachable.contains(&maze.find_target().unwrap()));
+        assert!(maze.reachable_from(Position::new(0, 0)).is_empty());
+    }
+
+    #[test]
+    fn neighbors_at_edges_and_corners() {
+        let maze = Maze::from_ascii("ffb\nfbf\nfft\n").unwrap();
+        let neighbors = |row, col| maze.neighbors(Position::new(row, col));
+        let at = Position::new;
+
+        assert_eq!(
+            neighbors(0, 0),
+            [
+                (at(1, 0), MoveDirection::Down),
+                (at(0, 1), MoveDirection::Right)
+            ]
+        );
+        assert_eq!(neighbors(0, 1), [(at(0, 0), MoveDirection::Left)]);
+        // a blocked cell still lists its walkable neighbors
+        assert_eq!(
+            neighbors(0, 2),
+            [
+                (at(1, 2), MoveDirection::Down),
+                (at(0, 1), MoveDirection::Left)
+            ]
+        );
+        assert_eq!(neighbors(1, 2), [(at(2, 2), MoveDirection::Down)]);
+        assert_eq!(
+            neighbors(2, 2),
+            [
+                (at(1, 2), MoveDirection::Up),
+                (at(2, 1), MoveDirection::Left)
+            ]
+        );
+        assert_eq!(
+            neighbors(1, 1),
+            [
+                (at(0, 1), MoveDirection::Up),
+                (at(2, 1), MoveDirection::Down),
+                (at(1, 0), MoveDirection::Left),
+                (at(1, 2), MoveDirection::Right),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_ascii_reads_dimensions_robot_and_target() {
+        let maze = Maze::from_ascii("bbbbb\nbrfbb\nbbftb\n").unwrap();
+
+        assert_eq!((maze.height(), maze.width()), (3, 5));
+        assert_eq!(maze.find_robot(), Some(Position::new(1, 1)));
+        assert_eq!(maze.find_target(), Some(Position::new(2, 3)));
+
+        // comma-separated rows, any case and surrounding blank lines read the same
+        let csv = Maze::from_ascii("\n b, b ,B\nR,f,T\n\n").unwrap();
+        assert_eq!((csv.height(), csv.width()), (2, 3));
+        assert_eq!(csv.find_robot(), Some(Position::new(1, 0)));
+        assert_eq!(csv.find_target(), Some(Position::new(1, 2)));
+        assert_eq!(csv, Maze::from_ascii("bbb\nrft\n").unwrap());
+
+        let err = Maze::from_ascii("rft\nbb\n").unwrap_err();
+        assert_eq!(err.to_string(), "row 1 has 2 cells, expected 3");
+    }
+
+    #[test]
+    fn equal_mazes_hash_equal_and_one_cell_tells_them_apart() {
+        let flattened = |grid: &str| grid.chars().map(|c| c.to_string()).collect();
+        let maze =
+            |grid: &str, shape: Vec<u8>| Maze::from_flattened(flattened(grid), shape).unwrap();
+        let hasher = RandomState::new();
+
+        let a = maze("rffbft", vec![2, 3]);
+        let b = maze("rffbft", vec![2, 3]);
+        assert_eq!(a, b);
+        assert_eq!(hasher.hash_one(&a), hasher.hash_one(&b));
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        let mut changed = a.clone();
+        changed.set(Position::new(0, 1), Cell::Blocked);
+        assert_ne!(a, changed);
+        assert_ne!(hasher.hash_one(&a), hasher.hash_one(&changed));
+        assert_ne!(a.content_hash(), changed.content_hash());
+
+        // the same cells in another shape are another maze
+        let reshaped = maze("rffbft", vec![3, 2]);
+        assert_ne!(a, reshaped);
+        assert_ne!(a.content_hash(), reshaped.content_hash());
+    }
+
+    #[test]
+    fn from_flattened_limited_rejects_grids_over_the_cap() {
+        let cells = |count| vec!["f".to_string(); count];
+
+        let err = Maze::from_flattened_limited(cells(6), vec![2, 3], 5).unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds the limit of 5"),
+            "{}",
+            err
+        );
+
+        // a grid longer than its shape claims is still held to the cap
+        let err = Maze::from_flattened_limited(cells(100), vec![1, 1], 5).unwrap_err();
+        assert!(err.to_string().contains("100 cells exceeds"), "{}", err);
+
+        assert!(Maze::from_flattened_limited(cells(6), vec![2, 3], 6).is_ok());
+    }
+
+    #[test]
+    fn from_flattened_strict_rejects_each_malformed_map() {
+        let strict = |grid: &str, shape: Vec<u8>| {
+            let flattened = grid.chars().map(|c| c.to_string()).collect();
+            Maze::from_flattened_strict(flattened, shape, DEFAULT_MAX_CELLS)
+        };
+        let error = |grid, shape| strict(grid, shape).unwrap_err().to_string();
+
+        assert!(strict("rft", vec![1, 3]).is_ok());
+        assert_eq!(error("fft", vec![1, 3]), "map has no robot cell");
+        assert_eq!(error("rff", vec![1, 3]), "map has no target cell");
+        assert_eq!(
+            error("rrt", vec![1, 3]),
+            "map has 2 robot cells, expected one"
+        );
+        assert_eq!(
+            error("rft", vec![2, 3]),
+            "grid size mismatch: expected 6, got 3"
+        );
+        assert_eq!(
+            error("rxt?", vec![1, 4]),
+            "unrecognized cell symbol \"x\" at index 1 (2 unrecognized in total)"
+        );
+    }
+
+    #[test]
+    fn out_of_range_positions_read_as_none() {
+        let mut maze = Maze::from_ascii("rf\nft\n").unwrap();
+        let outside = [
+            Position::new(2, 0),
+            Position::new(0, 2),
+            Position::new(usize::MAX, usize::MAX),
+        ];
+
+        for pos in outside {
+            assert_eq!(maze.get(pos), None);
+            assert!(!maze.is_walkable(pos));
+            assert_eq!(maze.cost(pos), 1);
+            maze.set(pos, Cell::Blocked);
+        }
+        assert_eq!(maze, Maze::from_ascii("rf\nft\n").unwrap());
+        assert_eq!(maze.get(Position::new(1, 1)), Some(Cell::Target));
+    }
+}
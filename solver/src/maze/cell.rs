@@ -1,8 +1,13 @@
 use crate::ros::types::SensorState;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// what entering a `Cell::Slow` costs, against 1 for every other walkable cell
+pub const SLOW_COST: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Cell {
     Free,
+    /// walkable terrain that takes `SLOW_COST` to enter; only appears in map files
+    Slow,
     Blocked,
     Target,
     Robot,
@@ -10,18 +15,46 @@ pub enum Cell {
 }
 
 impl Cell {
-    pub fn from_str(s: &str) -> Self {
+    /// anything unrecognized reads as `Unknown`, see `parse_symbol` to tell the two apart
+    pub fn from_symbol(s: &str) -> Self {
+        Self::parse_symbol(s).unwrap_or(Self::Unknown)
+    }
+
+    /// `None` for a symbol that is not one `symbol` produces
+    pub fn parse_symbol(s: &str) -> Option<Self> {
         match s {
-            "f" => Self::Free,
-            "b" => Self::Blocked,
-            "t" => Self::Target,
-            "r" => Self::Robot,
-            _ => Self::Unknown,
+            "f" => Some(Self::Free),
+            "s" => Some(Self::Slow),
+            "b" => Some(Self::Blocked),
+            "t" => Some(Self::Target),
+            "r" => Some(Self::Robot),
+            "u" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+
+    /// inverse of `from_symbol`, the symbol map files use for this cell
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Free => "f",
+            Self::Slow => "s",
+            Self::Blocked => "b",
+            Self::Target => "t",
+            Self::Robot => "r",
+            Self::Unknown => "u",
         }
     }
 
     pub fn is_walkable(self) -> bool {
-        matches!(self, Self::Free | Self::Target | Self::Robot)
+        matches!(self, Self::Free | Self::Slow | Self::Target | Self::Robot)
+    }
+
+    /// cost of moving onto this cell for the cost-aware pathfinders (dijkstra and a*)
+    pub fn cost(self) -> usize {
+        match self {
+            Self::Slow => SLOW_COST,
+            _ => 1,
+        }
     }
 }
 
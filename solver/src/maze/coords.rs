@@ -0,0 +1,34 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+
+/// how positions are written in human-facing output; internally everything stays row/col
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum CoordStyle {
+    /// `(row, col)`
+    #[default]
+    Rowcol,
+    /// `(x=col, y=row)`, x growing rightwards and y downwards as in the rendered maze
+    Xy,
+}
+
+static COORD_STYLE: OnceLock<CoordStyle> = OnceLock::new();
+
+impl CoordStyle {
+    /// configured once at startup, like the logger
+    pub fn init(self) {
+        let _ = COORD_STYLE.set(self);
+    }
+}
+
+pub(super) fn write_coords(
+    f: &mut fmt::Formatter<'_>,
+    row: impl fmt::Display,
+    col: impl fmt::Display,
+) -> fmt::Result {
+    match COORD_STYLE.get().copied().unwrap_or_default() {
+        CoordStyle::Rowcol => write!(f, "({}, {})", row, col),
+        CoordStyle::Xy => write!(f, "(x={}, y={})", col, row),
+    }
+}
@@ -0,0 +1,35 @@
+use clap::ValueEnum;
+
+/// how diagonal moves may pass wall corners
+///
+/// a diagonal step from `S` to `D` sweeps past the two orthogonal cells `A` and `B`:
+///
+/// ```text
+///     S A          S #          S #
+///     B D          B D          # D
+///
+///   forbid      allow-one-wall  allow-both
+/// ```
+///
+/// - `forbid`: both `A` and `B` must be walkable, the robot never touches a corner
+/// - `allow-one-wall`: one of `A` or `B` may be a wall, the robot grazes the corner
+/// - `allow-both`: the step is taken even when `A` and `B` are walls, squeezing
+///   between two corners (not physically possible for a real robot)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, ValueEnum)]
+pub enum CornerPolicy {
+    #[default]
+    Forbid,
+    AllowOneWall,
+    AllowBoth,
+}
+
+impl CornerPolicy {
+    /// whether a diagonal step is allowed given the walkability of its two orthogonal components
+    pub fn allows(self, a_walkable: bool, b_walkable: bool) -> bool {
+        match self {
+            Self::Forbid => a_walkable && b_walkable,
+            Self::AllowOneWall => a_walkable || b_walkable,
+            Self::AllowBoth => true,
+        }
+    }
+}
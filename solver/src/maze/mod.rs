@@ -1,9 +1,13 @@
 mod bounded;
 mod cell;
+mod coords;
+mod corner;
 mod position;
 mod unbounded;
 
-pub use bounded::Maze as BoundedMaze;
-pub use cell::Cell;
+pub use bounded::{DEFAULT_MAX_CELLS, Maze as BoundedMaze};
+pub use cell::{Cell, SLOW_COST};
+pub use coords::CoordStyle;
+pub use corner::CornerPolicy;
 pub use position::Position;
 pub use unbounded::{UnboundedMaze, UnboundedPosition};
@@ -1,5 +1,7 @@
+use std::fmt;
 use std::ops::Add;
 
+use super::coords::write_coords;
 use crate::ros::types::MoveDirection;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -8,6 +10,12 @@ pub struct Position {
     pub col: usize,
 }
 
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_coords(f, self.row, self.col)
+    }
+}
+
 impl Position {
     pub const fn new(row: usize, col: usize) -> Self {
         Self { row, col }
@@ -25,45 +33,163 @@ impl Position {
         self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
     }
 
+    /// straight-line distance between cell centers
+    pub fn euclidean_distance(self, other: Self) -> f64 {
+        let (rows, cols) = (self.row.abs_diff(other.row), self.col.abs_diff(other.col));
+        ((rows * rows + cols * cols) as f64).sqrt()
+    }
+
+    /// larger of the row and col deltas, the step count when diagonal moves are allowed
+    pub fn chebyshev_distance(self, other: Self) -> usize {
+        self.row
+            .abs_diff(other.row)
+            .max(self.col.abs_diff(other.col))
+    }
+
+    /// the cardinal move from `self` onto `other`, `None` unless they are orthogonally adjacent
+    pub fn direction_to(self, other: Self) -> Option<MoveDirection> {
+        MoveDirection::all()
+            .into_iter()
+            .find(|direction| self + direction.offset() == Some(other))
+    }
+
     pub fn move_in_direction(
         self,
         direction: MoveDirection,
         bounds: (usize, usize),
     ) -> Option<Self> {
+        self.checked_offset(direction.offset(), bounds)
+    }
+
+    /// `self + offset`, only if it lands inside a maze of `bounds` (height, width)
+    pub fn checked_offset(self, offset: (isize, isize), bounds: (usize, usize)) -> Option<Self> {
         let (height, width) = bounds;
-        match direction {
-            MoveDirection::Up if self.row > 0 => Some(Self::new(self.row - 1, self.col)),
-            MoveDirection::Down if self.row < height - 1 => Some(Self::new(self.row + 1, self.col)),
-            MoveDirection::Left if self.col > 0 => Some(Self::new(self.row, self.col - 1)),
-            MoveDirection::Right if self.col < width - 1 => Some(Self::new(self.row, self.col + 1)),
-            _ => None,
-        }
+        (self + offset).filter(|pos| pos.row < height && pos.col < width)
     }
 
-    pub fn neighbors(self, bounds: (usize, usize)) -> Vec<(Self, MoveDirection)> {
-        [
-            MoveDirection::Up,
-            MoveDirection::Down,
-            MoveDirection::Left,
-            MoveDirection::Right,
-        ]
-        .into_iter()
-        .filter_map(|dir| self.move_in_direction(dir, bounds).map(|pos| (pos, dir)))
-        .collect()
+    /// in-bounds neighbors regardless of walls, cardinal ones first and then the diagonals when
+    /// `diagonal` is set; pathfinders must go through `BoundedMaze::neighbors`
+    pub(super) fn neighbors(
+        self,
+        bounds: (usize, usize),
+        diagonal: bool,
+    ) -> Vec<(Self, MoveDirection)> {
+        let diagonals = MoveDirection::diagonals().into_iter().filter(|_| diagonal);
+
+        MoveDirection::all()
+            .into_iter()
+            .chain(diagonals)
+            .filter_map(|dir| self.move_in_direction(dir, bounds).map(|pos| (pos, dir)))
+            .collect()
     }
 }
 
+/// `None` only when a coordinate would go negative (or overflow); there is no upper bound,
+/// so the result may lie outside any maze. use `checked_offset` before indexing a grid, or go
+/// through `BoundedMaze::get`, which bounds-checks
 impl Add<(isize, isize)> for Position {
     type Output = Option<Self>;
 
     fn add(self, (dr, dc): (isize, isize)) -> Self::Output {
-        let new_row = self.row as isize + dr;
-        let new_col = self.col as isize + dc;
+        Some(Self::new(
+            self.row.checked_add_signed(dr)?,
+            self.col.checked_add_signed(dc)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_between_two_cells() {
+        let a = Position::new(1, 2);
+        let b = Position::new(4, 6);
+
+        assert_eq!(a.euclidean_distance(b), 5.0);
+        assert_eq!(b.euclidean_distance(a), 5.0);
+        assert_eq!(a.euclidean_distance(a), 0.0);
+        assert_eq!(a.chebyshev_distance(b), 4);
+        assert_eq!(b.chebyshev_distance(a), 4);
+        assert_eq!(a.chebyshev_distance(Position::new(2, 3)), 1);
+    }
+
+    #[test]
+    fn direction_to_only_names_orthogonal_neighbours() {
+        let center = Position::new(2, 2);
+
+        assert_eq!(
+            center.direction_to(Position::new(1, 2)),
+            Some(MoveDirection::Up)
+        );
+        assert_eq!(
+            center.direction_to(Position::new(3, 2)),
+            Some(MoveDirection::Down)
+        );
+        assert_eq!(
+            center.direction_to(Position::new(2, 1)),
+            Some(MoveDirection::Left)
+        );
+        assert_eq!(
+            center.direction_to(Position::new(2, 3)),
+            Some(MoveDirection::Right)
+        );
 
-        if new_row >= 0 && new_col >= 0 {
-            Some(Self::new(new_row as usize, new_col as usize))
-        } else {
-            None
+        assert_eq!(center.direction_to(Position::new(1, 1)), None);
+        assert_eq!(center.direction_to(Position::new(3, 3)), None);
+        assert_eq!(center.direction_to(Position::new(2, 4)), None);
+        assert_eq!(center.direction_to(Position::new(0, 2)), None);
+        assert_eq!(center.direction_to(center), None);
+    }
+
+    #[test]
+    fn add_matches_checked_arithmetic_at_the_edges() {
+        let coords = [0, 1, 2, 7, usize::MAX - 1, usize::MAX];
+        let offsets = [isize::MIN, -3, -2, -1, 0, 1, 2, 3, isize::MAX];
+
+        for row in coords {
+            for col in coords {
+                for dr in offsets {
+                    for dc in offsets {
+                        let checked = |coord: usize, delta: isize| {
+                            let sum = coord as i128 + delta as i128;
+                            (0..=usize::MAX as i128)
+                                .contains(&sum)
+                                .then_some(sum as usize)
+                        };
+                        let expected = checked(row, dr)
+                            .zip(checked(col, dc))
+                            .map(|(row, col)| Position::new(row, col));
+
+                        assert_eq!(
+                            Position::new(row, col) + (dr, dc),
+                            expected,
+                            "({}, {}) + ({}, {})",
+                            row,
+                            col,
+                            dr,
+                            dc
+                        );
+                    }
+                }
+            }
         }
     }
+
+    #[test]
+    fn checked_offset_stays_inside_the_bounds() {
+        let bounds = (3, 4);
+
+        assert_eq!(Position::new(0, 0).checked_offset((-1, 0), bounds), None);
+        assert_eq!(Position::new(0, 0).checked_offset((0, -1), bounds), None);
+        assert_eq!(Position::new(2, 3).checked_offset((1, 0), bounds), None);
+        assert_eq!(Position::new(2, 3).checked_offset((0, 1), bounds), None);
+        assert_eq!(
+            Position::new(2, 3).checked_offset((-2, -3), bounds),
+            Some(Position::new(0, 0))
+        );
+        // the unchecked sum happily leaves the maze
+        assert_eq!(Position::new(2, 3) + (1, 1), Some(Position::new(3, 4)));
+    }
 }
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-use super::cell::Cell;
+use super::{cell::Cell, coords::write_coords, position::Position};
 use crate::ros::types::{MoveDirection, SensorState, SensorsStates};
 
 /// position with signed coordinates for unbounded exploration
@@ -10,40 +11,73 @@ pub struct UnboundedPosition {
     pub col: isize,
 }
 
+impl fmt::Display for UnboundedPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_coords(f, self.row, self.col)
+    }
+}
+
 impl UnboundedPosition {
     pub fn new(row: isize, col: isize) -> Self {
         Self { row, col }
     }
 
     pub fn move_in_direction(&self, direction: MoveDirection) -> UnboundedPosition {
-        match direction {
-            MoveDirection::Up => Self::new(self.row - 1, self.col),
-            MoveDirection::Down => Self::new(self.row + 1, self.col),
-            MoveDirection::Left => Self::new(self.row, self.col - 1),
-            MoveDirection::Right => Self::new(self.row, self.col + 1),
-        }
+        let (dr, dc) = direction.offset();
+        Self::new(self.row + dr, self.col + dc)
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
     }
 
     pub fn neighbors(&self) -> impl Iterator<Item = (UnboundedPosition, MoveDirection)> {
-        [
-            (Self::new(self.row - 1, self.col), MoveDirection::Up),
-            (Self::new(self.row + 1, self.col), MoveDirection::Down),
-            (Self::new(self.row, self.col - 1), MoveDirection::Left),
-            (Self::new(self.row, self.col + 1), MoveDirection::Right),
-        ]
-        .into_iter()
+        MoveDirection::all()
+            .into_iter()
+            .map(|direction| (self.move_in_direction(direction), direction))
+    }
+
+    /// position relative to `origin`, the top-left corner of a bounded grid; `None` if it lies
+    /// above or left of it instead of wrapping around
+    pub fn to_bounded(self, origin: UnboundedPosition) -> Option<Position> {
+        let row = usize::try_from(self.row.checked_sub(origin.row)?).ok()?;
+        let col = usize::try_from(self.col.checked_sub(origin.col)?).ok()?;
+        Some(Position::new(row, col))
     }
 }
 
+/// density of the explored region, for judging exploration efficiency
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExplorationStats {
+    /// free, target and robot cells seen so far
+    pub walkable: usize,
+    pub blocked: usize,
+    /// (walkable + blocked) over the bounding box area
+    pub fill_ratio: f64,
+}
+
+/// one 4-connected region of walkable cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Component {
+    pub size: usize,
+    /// no cell borders an unknown one, so exploration could not have joined it to anything else
+    pub sealed: bool,
+    pub has_robot: bool,
+}
+
 /// unbounded maze that grows dynamically using hashmap for sparse storage
+///
+/// at most one cell is ever `Robot`: placing the robot frees its previous cell
 pub struct UnboundedMaze {
     cells: HashMap<UnboundedPosition, Cell>,
+    robot: Option<UnboundedPosition>,
 }
 
 impl UnboundedMaze {
     pub fn new() -> Self {
         Self {
             cells: HashMap::new(),
+            robot: None,
         }
     }
 
@@ -52,12 +86,43 @@ impl UnboundedMaze {
         self.cells.get(&pos).copied().unwrap_or(Cell::Unknown)
     }
 
+    /// whether anything is known about this cell yet
+    pub fn is_explored(&self, pos: UnboundedPosition) -> bool {
+        self.get(pos) != Cell::Unknown
+    }
+
+    /// how many cells have been sensed, walls included
+    pub fn known_cells(&self) -> usize {
+        self.cells
+            .values()
+            .filter(|cell| **cell != Cell::Unknown)
+            .count()
+    }
+
+    /// setting `Cell::Robot` moves the robot here, leaving its old cell `Free`
     pub fn set(&mut self, pos: UnboundedPosition, cell: Cell) {
+        if cell == Cell::Robot {
+            self.place_robot(pos);
+            return;
+        }
+
+        if self.robot == Some(pos) {
+            self.robot = None;
+        }
         self.cells.insert(pos, cell);
     }
 
+    pub fn place_robot(&mut self, pos: UnboundedPosition) {
+        if let Some(previous) = self.robot.replace(pos)
+            && previous != pos
+        {
+            self.cells.insert(previous, Cell::Free);
+        }
+        self.cells.insert(pos, Cell::Robot);
+    }
+
     pub fn is_walkable(&self, pos: UnboundedPosition) -> bool {
-        matches!(self.get(pos), Cell::Free | Cell::Target | Cell::Robot)
+        self.get(pos).is_walkable()
     }
 
     pub fn neighbors(&self, pos: UnboundedPosition) -> Vec<(UnboundedPosition, MoveDirection)> {
@@ -67,27 +132,107 @@ impl UnboundedMaze {
     }
 
     pub fn get_bounds(&self) -> Option<(isize, isize, isize, isize)> {
-        if self.cells.is_empty() {
-            return None;
+        bounds_of(self.cells.keys())
+    }
+
+    /// ascii map of the bounding box, one line per row: `#` blocked, `.` free, `T` target,
+    /// `R` robot and `?` unknown; empty when nothing has been seen yet
+    pub fn render_ascii(&self) -> String {
+        let Some((min_row, max_row, min_col, max_col)) = self.get_bounds() else {
+            return String::new();
+        };
+
+        (min_row..=max_row)
+            .map(|row| {
+                (min_col..=max_col)
+                    .map(|col| match self.get(UnboundedPosition::new(row, col)) {
+                        Cell::Blocked => '#',
+                        Cell::Free => '.',
+                        Cell::Slow => '~',
+                        Cell::Target => 'T',
+                        Cell::Robot => 'R',
+                        Cell::Unknown => '?',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// bounds of the sensed cells only, trimming border rows and columns that hold nothing but
+    /// `Unknown` entries; walls stay, so the converted maze keeps its outer ring
+    pub fn trimmed_bounds(&self) -> Option<(isize, isize, isize, isize)> {
+        bounds_of(
+            self.cells
+                .iter()
+                .filter(|(_, cell)| **cell != Cell::Unknown)
+                .map(|(pos, _)| pos),
+        )
+    }
+
+    pub fn stats(&self) -> ExplorationStats {
+        let Some((min_row, max_row, min_col, max_col)) = self.get_bounds() else {
+            return ExplorationStats::default();
+        };
+
+        let walkable = self
+            .cells
+            .values()
+            .filter(|cell| cell.is_walkable())
+            .count();
+        let blocked = self
+            .cells
+            .values()
+            .filter(|cell| **cell == Cell::Blocked)
+            .count();
+        let area = ((max_row - min_row + 1) * (max_col - min_col + 1)) as usize;
+
+        ExplorationStats {
+            walkable,
+            blocked,
+            fill_ratio: (walkable + blocked) as f64 / area as f64,
         }
+    }
+
+    /// walkable regions, largest first
+    pub fn connected_components(&self) -> Vec<Component> {
+        let mut seen: HashSet<UnboundedPosition> = HashSet::new();
+        let mut components = Vec::new();
+
+        for (&start, cell) in &self.cells {
+            if !cell.is_walkable() || !seen.insert(start) {
+                continue;
+            }
 
-        let mut min_row = isize::MAX;
-        let mut max_row = isize::MIN;
-        let mut min_col = isize::MAX;
-        let mut max_col = isize::MIN;
+            let mut component = Component {
+                size: 0,
+                sealed: true,
+                has_robot: false,
+            };
+            let mut stack = vec![start];
+
+            while let Some(pos) = stack.pop() {
+                component.size += 1;
+                component.has_robot |= self.robot == Some(pos);
+
+                for (neighbor, _) in pos.neighbors() {
+                    if !self.is_explored(neighbor) {
+                        component.sealed = false;
+                    } else if self.is_walkable(neighbor) && seen.insert(neighbor) {
+                        stack.push(neighbor);
+                    }
+                }
+            }
 
-        for pos in self.cells.keys() {
-            min_row = min_row.min(pos.row);
-            max_row = max_row.max(pos.row);
-            min_col = min_col.min(pos.col);
-            max_col = max_col.max(pos.col);
+            components.push(component);
         }
 
-        Some((min_row, max_row, min_col, max_col))
+        components.sort_by_key(|c| std::cmp::Reverse(c.size));
+        components
     }
 
     pub fn update_from_sensors(&mut self, pos: UnboundedPosition, sensors: &SensorsStates) {
-        self.set(pos, Cell::Robot);
+        self.place_robot(pos);
         let sensor_data = [
             (pos.row - 1, pos.col, &sensors.up),
             (pos.row + 1, pos.col, &sensors.down),
@@ -111,8 +256,170 @@ impl UnboundedMaze {
     }
 }
 
+fn bounds_of<'a>(
+    positions: impl Iterator<Item = &'a UnboundedPosition>,
+) -> Option<(isize, isize, isize, isize)> {
+    positions.fold(None, |bounds, pos| {
+        let (min_row, max_row, min_col, max_col) =
+            bounds.unwrap_or((pos.row, pos.row, pos.col, pos.col));
+        Some((
+            min_row.min(pos.row),
+            max_row.max(pos.row),
+            min_col.min(pos.col),
+            max_col.max(pos.col),
+        ))
+    })
+}
+
 impl Default for UnboundedMaze {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensors(free: &[MoveDirection]) -> SensorsStates {
+        let mut sensors = SensorsStates {
+            up: SensorState::Blocked,
+            down: SensorState::Blocked,
+            left: SensorState::Blocked,
+            right: SensorState::Blocked,
+            up_left: SensorState::Blocked,
+            up_right: SensorState::Blocked,
+            down_left: SensorState::Blocked,
+            down_right: SensorState::Blocked,
+        };
+        for &direction in free {
+            sensors.set(direction, SensorState::Free);
+        }
+        sensors
+    }
+
+    #[test]
+    fn trimmed_bounds_drops_unknown_borders_and_keeps_walls() {
+        let mut maze = UnboundedMaze::new();
+        maze.update_from_sensors(
+            UnboundedPosition::new(0, 0),
+            &sensors(&[MoveDirection::Right]),
+        );
+        maze.update_from_sensors(
+            UnboundedPosition::new(0, 1),
+            &sensors(&[MoveDirection::Left]),
+        );
+        // a ring of never-sensed entries around the walls, as a merged or loaded map can hold
+        for col in -3..=4 {
+            maze.set(UnboundedPosition::new(-3, col), Cell::Unknown);
+            maze.set(UnboundedPosition::new(3, col), Cell::Unknown);
+        }
+
+        assert_eq!(maze.get_bounds(), Some((-3, 3, -3, 4)));
+        assert_eq!(maze.trimmed_bounds(), Some((-1, 1, -1, 2)));
+        assert_eq!(UnboundedMaze::new().trimmed_bounds(), None);
+    }
+
+    #[test]
+    fn render_ascii_draws_every_cell_kind() {
+        let mut maze = UnboundedMaze::new();
+        let at = UnboundedPosition::new;
+        assert_eq!(maze.render_ascii(), "");
+
+        maze.update_from_sensors(at(0, 0), &sensors(&[MoveDirection::Right]));
+        maze.update_from_sensors(at(0, 1), &sensors(&[MoveDirection::Left]));
+        maze.set(at(1, 0), Cell::Slow);
+        maze.set(at(1, 1), Cell::Target);
+        // only widens the bounding box, the rest of its row was never seen either
+        maze.set(at(2, 2), Cell::Unknown);
+
+        assert_eq!(
+            maze.render_ascii(),
+            "\
+####
+#.R#
+#~T#
+????"
+        );
+    }
+
+    #[test]
+    fn only_the_latest_robot_position_stays_robot() {
+        let mut maze = UnboundedMaze::new();
+        let open = SensorsStates::uniform(SensorState::Free);
+
+        maze.update_from_sensors(UnboundedPosition::new(0, 0), &open);
+        // far enough that the second reading does not cover the first cell
+        maze.update_from_sensors(UnboundedPosition::new(0, 5), &open);
+
+        let robots: Vec<_> = maze
+            .cells
+            .iter()
+            .filter(|(_, cell)| **cell == Cell::Robot)
+            .map(|(pos, _)| *pos)
+            .collect();
+        assert_eq!(robots, [UnboundedPosition::new(0, 5)]);
+        assert_eq!(maze.get(UnboundedPosition::new(0, 0)), Cell::Free);
+    }
+
+    #[test]
+    fn connected_components_finds_a_sealed_pocket() {
+        let mut maze = UnboundedMaze::new();
+        let at = UnboundedPosition::new;
+
+        // the robot's corridor, still open towards the unknown (0, 2)
+        maze.place_robot(at(0, 0));
+        maze.set(at(0, 1), Cell::Free);
+        for pos in [at(0, -1), at(-1, 0), at(1, 0), at(-1, 1), at(1, 1)] {
+            maze.set(pos, Cell::Blocked);
+        }
+        // two free cells walled in on every side
+        maze.set(at(0, 5), Cell::Free);
+        maze.set(at(0, 6), Cell::Target);
+        for pos in [at(0, 4), at(0, 7), at(-1, 5), at(1, 5), at(-1, 6), at(1, 6)] {
+            maze.set(pos, Cell::Blocked);
+        }
+
+        let mut components = maze.connected_components();
+        components.sort_by_key(|c| c.has_robot);
+
+        assert_eq!(
+            components,
+            [
+                Component {
+                    size: 2,
+                    sealed: true,
+                    has_robot: false,
+                },
+                Component {
+                    size: 2,
+                    sealed: false,
+                    has_robot: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn target_sighted_diagonally_stays_reachable() {
+        let mut maze = UnboundedMaze::new();
+        let robot = UnboundedPosition::new(0, 0);
+        let mut sensors = SensorsStates::uniform(SensorState::Free);
+        sensors.set(MoveDirection::UpRight, SensorState::Target);
+
+        maze.update_from_sensors(robot, &sensors);
+
+        let target = UnboundedPosition::new(-1, 1);
+        assert_eq!(maze.get(target), Cell::Target);
+        // both cardinal cells in between are mapped free, so a 4-connected path reaches it
+        for between in [UnboundedPosition::new(-1, 0), UnboundedPosition::new(0, 1)] {
+            assert_eq!(maze.get(between), Cell::Free);
+            assert!(maze.neighbors(robot).iter().any(|(pos, _)| *pos == between));
+            assert!(
+                maze.neighbors(between)
+                    .iter()
+                    .any(|(pos, _)| *pos == target)
+            );
+        }
+    }
+}
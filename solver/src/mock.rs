@@ -0,0 +1,317 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, watch};
+
+use crate::{
+    backend::{MazeBackend, RawMap, SensorFrame},
+    maze::{BoundedMaze, Cell, Position},
+    ros::types::{MoveDirection, SensorState, SensorsStates},
+};
+
+/// how often a sensor frame is published, a few times faster than the simulator
+const SENSOR_INTERVAL: Duration = Duration::from_millis(2);
+
+/// `MazeBackend` over an in-memory maze, for tests and for embedders without a simulator
+///
+/// moves succeed onto any walkable cell, the target included, and a sensor frame with the
+/// eight cells around the robot is published every `SENSOR_INTERVAL` until the backend is
+/// dropped. must be created inside a tokio runtime, which runs the publisher
+pub struct MockBackend {
+    state: Mutex<MockState>,
+    sensor_tx: broadcast::Sender<SensorsStates>,
+    frame_tx: watch::Sender<Option<SensorFrame>>,
+    moves: AtomicUsize,
+    requests: AtomicUsize,
+    refusals: AtomicUsize,
+    continuous: bool,
+}
+
+struct MockState {
+    /// the maze with the robot's cell cleared, the robot is drawn back in by `get_map`
+    maze: BoundedMaze,
+    start: Position,
+    robot: Position,
+    seq: u64,
+}
+
+impl MockBackend {
+    /// a backend with the robot on the maze's `Robot` cell
+    pub fn new(maze: BoundedMaze) -> eyre::Result<Arc<Self>> {
+        Self::build(maze, false)
+    }
+
+    /// like `new`, but reporting continuous-move support so solvers collapse straight runs;
+    /// mock moves are instant, so the default single-move `move_until_wall` stands in for them
+    pub fn with_continuous_moves(maze: BoundedMaze) -> eyre::Result<Arc<Self>> {
+        Self::build(maze, true)
+    }
+
+    fn build(mut maze: BoundedMaze, continuous: bool) -> eyre::Result<Arc<Self>> {
+        let start = maze
+            .find_robot()
+            .ok_or_else(|| eyre::eyre!("mock maze has no robot"))?;
+        if maze.height() > u8::MAX as usize || maze.width() > u8::MAX as usize {
+            eyre::bail!(
+                "{}x{} maze does not fit the simulator's u8 shape",
+                maze.height(),
+                maze.width()
+            );
+        }
+        maze.set(start, Cell::Free);
+
+        let (sensor_tx, _) = broadcast::channel(16);
+        let (frame_tx, _) = watch::channel(None);
+        let backend = Arc::new(Self {
+            state: Mutex::new(MockState {
+                maze,
+                start,
+                robot: start,
+                seq: 0,
+            }),
+            sensor_tx,
+            frame_tx,
+            moves: AtomicUsize::new(0),
+            requests: AtomicUsize::new(0),
+            refusals: AtomicUsize::new(0),
+            continuous,
+        });
+
+        tokio::spawn(Self::publish_sensors(Arc::downgrade(&backend)));
+        Ok(backend)
+    }
+
+    /// where the robot is right now
+    pub fn robot(&self) -> Position {
+        self.state.lock().unwrap().robot
+    }
+
+    /// successful moves since the backend was created, resets included
+    pub fn moves(&self) -> usize {
+        self.moves.load(Ordering::Relaxed)
+    }
+
+    /// move requests received, refused ones included
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// refuses the next `count` move requests even onto walkable cells, like the simulator
+    /// right after a reset
+    pub fn refuse_next_moves(&self, count: usize) {
+        self.refusals.store(count, Ordering::Relaxed);
+    }
+
+    async fn publish_sensors(backend: Weak<Self>) {
+        let mut interval = tokio::time::interval(SENSOR_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(backend) = backend.upgrade() else {
+                break;
+            };
+            backend.publish();
+        }
+    }
+
+    fn publish(&self) {
+        let frame = {
+            let mut state = self.state.lock().unwrap();
+            state.seq += 1;
+            SensorFrame {
+                seq: state.seq,
+                received_at: Instant::now(),
+                states: state.sensors(),
+            }
+        };
+        // nobody subscribed is fine, frames are only kept for whoever asks
+        let _ = self.sensor_tx.send(frame.states.clone());
+        self.frame_tx.send_replace(Some(frame));
+    }
+}
+
+impl MockState {
+    fn sensors(&self) -> SensorsStates {
+        let mut sensors = SensorsStates {
+            up: SensorState::Blocked,
+            down: SensorState::Blocked,
+            left: SensorState::Blocked,
+            right: SensorState::Blocked,
+            up_left: SensorState::Blocked,
+            up_right: SensorState::Blocked,
+            down_left: SensorState::Blocked,
+            down_right: SensorState::Blocked,
+        };
+
+        for direction in MoveDirection::all()
+            .into_iter()
+            .chain(MoveDirection::diagonals())
+        {
+            let cell = self
+                .robot
+                .move_in_direction(direction, self.maze.bounds())
+                .and_then(|pos| self.maze.get(pos));
+            let state = match cell {
+                Some(Cell::Target) => SensorState::Target,
+                Some(cell) if cell.is_walkable() => SensorState::Free,
+                _ => SensorState::Blocked,
+            };
+            sensors.set(direction, state);
+        }
+
+        sensors
+    }
+}
+
+impl MazeBackend for MockBackend {
+    async fn get_map(&self) -> eyre::Result<RawMap> {
+        let state = self.state.lock().unwrap();
+        let (height, width) = state.maze.bounds();
+
+        let mut flattened = Vec::with_capacity(height * width);
+        for row in 0..height {
+            for col in 0..width {
+                let pos = Position::new(row, col);
+                let cell = if pos == state.robot {
+                    Cell::Robot
+                } else {
+                    state.maze.get(pos).unwrap_or(Cell::Blocked)
+                };
+                flattened.push(cell.symbol().to_string());
+            }
+        }
+
+        Ok(RawMap {
+            occupancy_grid_flattened: flattened,
+            occupancy_grid_shape: vec![height as u8, width as u8],
+        })
+    }
+
+    async fn move_cmd(&self, direction: MoveDirection) -> eyre::Result<bool> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let refused = self
+            .refusals
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok();
+        if refused {
+            return Ok(false);
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let next = state
+            .robot
+            .move_in_direction(direction, state.maze.bounds())
+            .filter(|&pos| state.maze.is_walkable(pos));
+
+        match next {
+            Some(pos) => {
+                state.robot = pos;
+                self.moves.fetch_add(1, Ordering::Relaxed);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn reset(&self) -> eyre::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.robot = state.start;
+        Ok(())
+    }
+
+    fn subscribe_sensors(&self) -> broadcast::Receiver<SensorsStates> {
+        self.sensor_tx.subscribe()
+    }
+
+    fn watch_sensors(&self) -> watch::Receiver<Option<SensorFrame>> {
+        self.frame_tx.subscribe()
+    }
+
+    fn latest_sensors(&self) -> Option<SensorFrame> {
+        self.frame_tx.borrow().clone()
+    }
+
+    fn supports_continuous_moves(&self) -> bool {
+        self.continuous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::sensors_since;
+
+    const CORRIDOR: &str = "bbbbb\nbrftb\nbbbbb\n";
+
+    #[tokio::test]
+    async fn sensors_since_sees_the_latest_of_two_readings() {
+        let backend = MockBackend::new(BoundedMaze::from_ascii(CORRIDOR).unwrap()).unwrap();
+        let mut sensor_rx = backend.watch_sensors();
+        backend.publish();
+        let stale = backend.latest_sensors().unwrap().seq;
+
+        let moved_at = Instant::now();
+        backend.publish();
+        backend.publish();
+        let latest = backend.latest_sensors().unwrap().seq;
+
+        let frame = sensors_since(&mut sensor_rx, moved_at).await.unwrap();
+        assert!(latest >= stale + 2);
+        assert_eq!(frame.seq, latest);
+        assert!(frame.received_at >= moved_at);
+    }
+
+    #[tokio::test]
+    async fn subscribers_get_the_sensors_around_the_robot_after_a_move() {
+        let backend = MockBackend::new(BoundedMaze::from_ascii(CORRIDOR).unwrap()).unwrap();
+        let mut sensor_rx = backend.subscribe_sensors();
+
+        let mut at_start = SensorsStates::uniform(SensorState::Blocked);
+        at_start.right = SensorState::Free;
+        assert_eq!(sensor_rx.recv().await.unwrap(), at_start);
+
+        assert!(backend.move_cmd(MoveDirection::Right).await.unwrap());
+
+        let mut moved = SensorsStates::uniform(SensorState::Blocked);
+        moved.left = SensorState::Free;
+        moved.right = SensorState::Target;
+        // frames already queued from the start are skipped, the first one after them is checked
+        let frame = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                let frame = sensor_rx.recv().await.unwrap();
+                if frame != at_start {
+                    break frame;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(frame, moved);
+    }
+
+    #[tokio::test]
+    async fn move_cmd_retry_outlasts_refused_moves() {
+        let backend = MockBackend::new(BoundedMaze::from_ascii(CORRIDOR).unwrap()).unwrap();
+        let backoff = Duration::from_millis(1);
+
+        backend.refuse_next_moves(2);
+        assert!(
+            !backend
+                .move_cmd_retry(MoveDirection::Right, 1, backoff)
+                .await
+                .unwrap()
+        );
+        assert_eq!(backend.robot(), Position::new(1, 1));
+
+        backend.refuse_next_moves(2);
+        assert!(
+            backend
+                .move_cmd_retry(MoveDirection::Right, 2, backoff)
+                .await
+                .unwrap()
+        );
+        assert_eq!(backend.robot(), Position::new(1, 2));
+        assert_eq!(backend.requests(), 5);
+    }
+}
@@ -0,0 +1,441 @@
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::pathfinding::PathResult;
+
+/// complete record of a benchmark sweep, failed runs included
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub mode: String,
+    pub rows: Vec<BenchmarkRow>,
+}
+
+/// one attempted algorithm (or combination), in canonical order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchmarkRow {
+    pub algorithm: String,
+    #[serde(flatten)]
+    pub outcome: RunOutcome,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RunOutcome {
+    Completed {
+        steps: usize,
+        path_length: usize,
+        planning_us: u64,
+        execution_us: u64,
+        total_us: u64,
+        /// up, down, left, right
+        direction_counts: [usize; 4],
+        /// absent from reports written before the field existed
+        #[serde(default)]
+        nodes_expanded: usize,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl BenchmarkRow {
+    pub fn completed(algorithm: &str, result: &PathResult) -> Self {
+        Self {
+            algorithm: algorithm.to_string(),
+            outcome: RunOutcome::Completed {
+                steps: result.steps,
+                path_length: result.path.len(),
+                planning_us: result.planning_time.as_micros() as u64,
+                execution_us: result.execution_time.as_micros() as u64,
+                total_us: result.total_time.as_micros() as u64,
+                direction_counts: result.direction_counts,
+                nodes_expanded: result.nodes_expanded,
+            },
+        }
+    }
+
+    pub fn failed(algorithm: &str, error: &str) -> Self {
+        Self {
+            algorithm: algorithm.to_string(),
+            outcome: RunOutcome::Failed {
+                error: error.to_string(),
+            },
+        }
+    }
+}
+
+impl BenchmarkReport {
+    /// merges successes and failures back into canonical order
+    pub fn new(
+        mode: &str,
+        completed: &[(usize, String, PathResult)],
+        failed: &[(usize, String, String)],
+    ) -> Self {
+        let mut rows: Vec<(usize, BenchmarkRow)> = completed
+            .iter()
+            .map(|(idx, name, result)| (*idx, BenchmarkRow::completed(name, result)))
+            .chain(
+                failed
+                    .iter()
+                    .map(|(idx, name, error)| (*idx, BenchmarkRow::failed(name, error))),
+            )
+            .collect();
+        rows.sort_by_key(|(idx, _)| *idx);
+
+        Self {
+            mode: mode.to_string(),
+            rows: rows.into_iter().map(|(_, row)| row).collect(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| eyre::eyre!("{}: not a benchmark report: {}", path.display(), e))
+    }
+
+    /// pairs rows by algorithm name, in `new`'s order followed by rows only `self` has
+    pub fn diff(&self, new: &Self) -> Vec<RowDiff> {
+        let mut diffs: Vec<RowDiff> = new
+            .rows
+            .iter()
+            .map(|row| {
+                let change = match (self.outcome(&row.algorithm), &row.outcome) {
+                    (None, _) => Change::Added,
+                    (
+                        Some(RunOutcome::Completed {
+                            steps: old_steps,
+                            total_us: old_total,
+                            ..
+                        }),
+                        RunOutcome::Completed {
+                            steps, total_us, ..
+                        },
+                    ) => Change::Compared {
+                        steps: *steps as i64 - *old_steps as i64,
+                        total_us: *total_us as i64 - *old_total as i64,
+                    },
+                    (Some(RunOutcome::Completed { .. }), RunOutcome::Failed { .. }) => {
+                        Change::NowFailing
+                    }
+                    (Some(RunOutcome::Failed { .. }), RunOutcome::Completed { .. }) => {
+                        Change::NowPassing
+                    }
+                    (Some(RunOutcome::Failed { .. }), RunOutcome::Failed { .. }) => {
+                        Change::StillFailing
+                    }
+                };
+                RowDiff {
+                    algorithm: row.algorithm.clone(),
+                    change,
+                }
+            })
+            .collect();
+
+        diffs.extend(
+            self.rows
+                .iter()
+                .filter(|row| new.outcome(&row.algorithm).is_none())
+                .map(|row| RowDiff {
+                    algorithm: row.algorithm.clone(),
+                    change: Change::Removed,
+                }),
+        );
+        diffs
+    }
+
+    fn outcome(&self, algorithm: &str) -> Option<&RunOutcome> {
+        self.rows
+            .iter()
+            .find(|row| row.algorithm == algorithm)
+            .map(|row| &row.outcome)
+    }
+}
+
+/// how one algorithm's row changed between two reports
+#[derive(Debug)]
+pub struct RowDiff {
+    pub algorithm: String,
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// completed in both; deltas are new minus old
+    Compared {
+        steps: i64,
+        total_us: i64,
+    },
+    NowFailing,
+    NowPassing,
+    StillFailing,
+    /// only in the new report
+    Added,
+    /// only in the old report
+    Removed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Regression,
+    Improvement,
+    Unchanged,
+}
+
+impl Change {
+    /// fewer steps wins outright; equal steps fall back to total time
+    pub fn verdict(self) -> Verdict {
+        match self {
+            Self::Compared { steps, total_us } => match steps.cmp(&0).then(total_us.cmp(&0)) {
+                std::cmp::Ordering::Greater => Verdict::Regression,
+                std::cmp::Ordering::Less => Verdict::Improvement,
+                std::cmp::Ordering::Equal => Verdict::Unchanged,
+            },
+            Self::NowFailing => Verdict::Regression,
+            Self::NowPassing => Verdict::Improvement,
+            Self::StillFailing | Self::Added | Self::Removed => Verdict::Unchanged,
+        }
+    }
+}
+
+/// spread of steps and total time over repeated runs of one algorithm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchmarkStats {
+    pub runs: usize,
+    pub mean_steps: f64,
+    pub median_steps: f64,
+    pub stddev_steps: f64,
+    pub mean_total: Duration,
+    pub stddev_total: Duration,
+}
+
+impl BenchmarkStats {
+    /// population mean and standard deviation, `None` without any run
+    pub fn from_results(results: &[PathResult]) -> Option<Self> {
+        let steps: Vec<usize> = results.iter().map(|result| result.steps).collect();
+        let step_stats = StepStats::from_steps(&steps)?;
+        let (mean_total, stddev_total) =
+            mean_stddev(results.iter().map(|result| result.total_time.as_secs_f64()));
+
+        Some(Self {
+            runs: results.len(),
+            mean_steps: step_stats.mean,
+            median_steps: step_stats.median,
+            stddev_steps: step_stats.stddev,
+            mean_total: Duration::from_secs_f64(mean_total),
+            stddev_total: Duration::from_secs_f64(stddev_total),
+        })
+    }
+}
+
+/// distribution of step counts over repeated runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepStats {
+    pub mean: f64,
+    /// middle count, or the mean of the two middle ones for an even number of runs
+    pub median: f64,
+    /// population standard deviation
+    pub stddev: f64,
+    pub min: usize,
+    pub max: usize,
+}
+
+impl StepStats {
+    /// `None` without any run
+    pub fn from_steps(steps: &[usize]) -> Option<Self> {
+        let mut sorted = steps.to_vec();
+        sorted.sort_unstable();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) as f64 / 2.0
+        } else {
+            sorted[middle] as f64
+        };
+        let (mean, stddev) = mean_stddev(sorted.iter().map(|&steps| steps as f64));
+
+        Some(Self {
+            mean,
+            median,
+            stddev,
+            min,
+            max,
+        })
+    }
+}
+
+fn mean_stddev(values: impl Iterator<Item = f64> + Clone) -> (f64, f64) {
+    let count = values.clone().count() as f64;
+    let mean = values.clone().sum::<f64>() / count;
+    let variance = values.map(|value| (value - mean).powi(2)).sum::<f64>() / count;
+    (mean, variance.sqrt())
+}
+
+/// one completed run, flattened for plotting and regression tracking
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    pub algorithm: String,
+    pub steps: usize,
+    pub planning_ms: f64,
+    pub execution_ms: f64,
+    pub total_ms: f64,
+}
+
+impl BenchmarkRecord {
+    pub fn new(algorithm: &str, result: &PathResult) -> Self {
+        Self {
+            algorithm: algorithm.to_string(),
+            steps: result.steps,
+            planning_ms: result.planning_time.as_secs_f64() * 1000.0,
+            execution_ms: result.execution_time.as_secs_f64() * 1000.0,
+            total_ms: result.total_time.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// machine-readable format for `write_benchmark_results`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BenchmarkFormat {
+    /// header row followed by one row per record
+    Csv,
+    /// pretty-printed array of records
+    Json,
+}
+
+/// writes `records` to `path` in `format`, or to stdout when no path is given
+pub fn write_benchmark_results(
+    records: &[BenchmarkRecord],
+    format: BenchmarkFormat,
+    path: Option<&Path>,
+) -> eyre::Result<()> {
+    let contents = match format {
+        BenchmarkFormat::Csv => records_to_csv(records),
+        BenchmarkFormat::Json => serde_json::to_string_pretty(records)? + "\n",
+    };
+
+    match path {
+        Some(path) => std::fs::write(path, contents)?,
+        None => std::io::stdout().write_all(contents.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn records_to_csv(records: &[BenchmarkRecord]) -> String {
+    let mut out = String::from("algorithm,steps,planning_ms,execution_ms,total_ms\n");
+    for record in records {
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&record.algorithm),
+            record.steps,
+            record.planning_ms,
+            record.execution_ms,
+            record.total_ms
+        ));
+    }
+    out
+}
+
+/// quotes a field holding a separator or quote, doubling any quotes inside
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<BenchmarkRecord> {
+        vec![
+            BenchmarkRecord {
+                algorithm: "A*".to_string(),
+                steps: 12,
+                planning_ms: 0.5,
+                execution_ms: 120.25,
+                total_ms: 120.75,
+            },
+            BenchmarkRecord {
+                algorithm: "Frontier, then \"BFS\"".to_string(),
+                steps: 40,
+                planning_ms: 1.0,
+                execution_ms: 400.0,
+                total_ms: 401.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn step_stats_of_a_fixed_sample() {
+        let stats = StepStats::from_steps(&[9, 2, 4, 5, 4, 7, 4, 5]).unwrap();
+
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.median, 4.5);
+        assert_eq!(stats.stddev, 2.0);
+        assert_eq!((stats.min, stats.max), (2, 9));
+
+        let odd = StepStats::from_steps(&[3, 1, 2]).unwrap();
+        assert_eq!(odd.median, 2.0);
+        assert!(StepStats::from_steps(&[]).is_none());
+    }
+
+    #[test]
+    fn benchmark_stats_average_steps_and_total_time() {
+        let result = |steps, total_ms| {
+            PathResult::new(steps, Duration::ZERO, Duration::from_millis(total_ms))
+        };
+        let stats =
+            BenchmarkStats::from_results(&[result(10, 100), result(14, 300), result(12, 200)])
+                .unwrap();
+
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.mean_steps, 12.0);
+        assert_eq!(stats.median_steps, 12.0);
+        assert!((stats.stddev_steps - (8.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(stats.mean_total, Duration::from_millis(200));
+        assert!(
+            stats.stddev_total.abs_diff(Duration::from_micros(81_650)) < Duration::from_micros(1)
+        );
+        assert!(BenchmarkStats::from_results(&[]).is_none());
+    }
+
+    #[test]
+    fn csv_has_the_header_then_one_row_per_record() {
+        assert_eq!(
+            records_to_csv(&records()),
+            "algorithm,steps,planning_ms,execution_ms,total_ms\n\
+             A*,12,0.5,120.25,120.75\n\
+             \"Frontier, then \"\"BFS\"\"\",40,1,400,401\n"
+        );
+    }
+
+    #[test]
+    fn json_output_reads_back_as_the_same_records() {
+        let path =
+            std::env::temp_dir().join(format!("benchmark-results-{}.json", std::process::id()));
+
+        write_benchmark_results(&records(), BenchmarkFormat::Json, Some(&path)).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(value[0]["algorithm"], "A*");
+        assert_eq!(value[0]["steps"], 12);
+        assert_eq!(value[0]["total_ms"], 120.75);
+
+        let loaded: Vec<BenchmarkRecord> = serde_json::from_str(&contents).unwrap();
+        assert_eq!(loaded, records());
+    }
+}
@@ -0,0 +1,74 @@
+/// node name and endpoint paths the interface talks to, all derived from one namespace so a
+/// simulator launched under a namespace can be reached without touching the code
+#[derive(Debug, Clone)]
+pub struct RosConfig {
+    pub node_name: String,
+    /// empty for the root namespace, otherwise `/`-prefixed without a trailing `/`
+    pub namespace: String,
+    pub get_map_service: String,
+    pub move_service: String,
+    pub reset_service: String,
+    pub sensor_topic: String,
+    /// where `--publish-progress` sends step and known-cell counts
+    pub progress_topic: String,
+}
+
+pub const DEFAULT_NODE_NAME: &str = "the_honored_solver";
+
+impl RosConfig {
+    pub fn new(node_name: &str, namespace: &str) -> Self {
+        let namespace = match namespace.trim_matches('/') {
+            "" => String::new(),
+            ns => format!("/{}", ns),
+        };
+
+        Self {
+            node_name: node_name.to_string(),
+            get_map_service: format!("{}/get_map", namespace),
+            move_service: format!("{}/move_command", namespace),
+            reset_service: format!("{}/reset", namespace),
+            sensor_topic: format!("{}/culling_games/robot_sensors", namespace),
+            progress_topic: format!("{}/{}/progress", namespace, node_name),
+            namespace,
+        }
+    }
+}
+
+impl Default for RosConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_NODE_NAME, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_namespace_keeps_the_simulator_defaults() {
+        let config = RosConfig::default();
+
+        assert_eq!(config.node_name, DEFAULT_NODE_NAME);
+        assert_eq!(config.namespace, "");
+        assert_eq!(config.get_map_service, "/get_map");
+        assert_eq!(config.move_service, "/move_command");
+        assert_eq!(config.reset_service, "/reset");
+        assert_eq!(config.sensor_topic, "/culling_games/robot_sensors");
+        assert_eq!(config.progress_topic, "/the_honored_solver/progress");
+        assert_eq!(RosConfig::new(DEFAULT_NODE_NAME, "/").namespace, "");
+    }
+
+    #[test]
+    fn namespace_prefixes_every_endpoint() {
+        for namespace in ["sim_1", "/sim_1", "/sim_1/"] {
+            let config = RosConfig::new("solver", namespace);
+
+            assert_eq!(config.namespace, "/sim_1");
+            assert_eq!(config.get_map_service, "/sim_1/get_map");
+            assert_eq!(config.move_service, "/sim_1/move_command");
+            assert_eq!(config.reset_service, "/sim_1/reset");
+            assert_eq!(config.sensor_topic, "/sim_1/culling_games/robot_sensors");
+            assert_eq!(config.progress_topic, "/sim_1/solver/progress");
+        }
+    }
+}
@@ -1,60 +1,163 @@
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use futures::StreamExt;
+use futures::{StreamExt, stream::BoxStream};
 use r2r::{
-    Client, Node, QosProfile, Result as R2RResult, WrappedServiceTypeSupport,
+    Client, Node, Publisher, QosProfile, Result as R2RResult, WrappedServiceTypeSupport,
     cg_interfaces::{
         msg::RobotSensors,
         srv::{GetMap, MoveCmd, Reset},
     },
+    std_msgs::msg::String as StringMsg,
 };
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
 
-use super::types::{MoveDirection, SensorsStates};
+use super::{
+    config::RosConfig,
+    types::{MoveDirection, SensorsStates},
+};
+use crate::backend::{MazeBackend, RawMap, SensorFrame};
+
+const MAX_RESUBSCRIBE_ATTEMPTS: usize = 3;
+const RESUBSCRIBE_BACKOFF: Duration = Duration::from_millis(500);
 
 pub struct ROSInterface {
     get_map_client: Client<GetMap::Service>,
     move_client: Client<MoveCmd::Service>,
     reset_client: Client<Reset::Service>,
-    sensor_tx: broadcast::Sender<SensorsStates>,
+    /// taken (and dropped) once the sensor stream ends for good, closing every receiver
+    sensor_tx: Mutex<Option<broadcast::Sender<SensorsStates>>>,
+    /// latest frame; the subscriber task owns the sender, so this closes when the task ends
+    sensor_watch: watch::Receiver<Option<SensorFrame>>,
+    get_map_calls: AtomicUsize,
+    move_calls: AtomicUsize,
+    reset_calls: AtomicUsize,
+    sensor_frames: AtomicUsize,
+    /// extra time every move takes before its response is returned, for exercising
+    /// timing-sensitive code against a known latency
+    simulated_latency: Duration,
+    /// how long a service call may take before it fails instead of blocking forever
+    request_timeout: Duration,
+    /// only created with `--publish-progress`, so the topic does not exist otherwise
+    progress_publisher: Option<Mutex<Publisher<StringMsg>>>,
+}
+
+/// how often the simulator was talked to since the interface was created
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InteractionStats {
+    pub get_map_calls: usize,
+    pub move_calls: usize,
+    pub reset_calls: usize,
+    /// sensor frames received from the topic, whether or not a solver read them
+    pub sensor_frames: usize,
 }
 
 impl ROSInterface {
-    pub fn new(node: &mut Node) -> eyre::Result<Arc<Self>> {
-        let get_map_client = create_client::<GetMap::Service>(node, "/get_map")?;
-        let move_client = create_client::<MoveCmd::Service>(node, "/move_command")?;
-        let reset_client = create_client::<Reset::Service>(node, "/reset")?;
-        let mut sensors_subscriber = node
-            .subscribe::<RobotSensors>("/culling_games/robot_sensors", QosProfile::sensor_data())?;
+    pub fn new(
+        config: &RosConfig,
+        node: Arc<Mutex<Node>>,
+        simulated_latency: Duration,
+        request_timeout: Duration,
+        publish_progress: bool,
+    ) -> eyre::Result<Arc<Self>> {
+        let (get_map_client, move_client, reset_client) = {
+            let mut node = lock_node(&node)?;
+            (
+                create_client::<GetMap::Service>(&mut node, &config.get_map_service)?,
+                create_client::<MoveCmd::Service>(&mut node, &config.move_service)?,
+                create_client::<Reset::Service>(&mut node, &config.reset_service)?,
+            )
+        };
+        let progress_publisher = if publish_progress {
+            let publisher = lock_node(&node)?
+                .create_publisher::<StringMsg>(&config.progress_topic, QosProfile::default())?;
+            log::debug!("publishing progress on {}", config.progress_topic);
+            Some(Mutex::new(publisher))
+        } else {
+            None
+        };
+        let sensor_topic = config.sensor_topic.clone();
+        let mut sensors_subscriber = subscribe_sensors_topic(&node, &sensor_topic)?;
 
         let (sensor_tx, _) = broadcast::channel(100);
+        let (frame_tx, sensor_watch) = watch::channel(None);
 
         let interface = Arc::new(Self {
             get_map_client,
             move_client,
             reset_client,
-            sensor_tx,
+            sensor_tx: Mutex::new(Some(sensor_tx.clone())),
+            sensor_watch,
+            get_map_calls: AtomicUsize::new(0),
+            move_calls: AtomicUsize::new(0),
+            reset_calls: AtomicUsize::new(0),
+            sensor_frames: AtomicUsize::new(0),
+            simulated_latency,
+            request_timeout,
+            progress_publisher,
         });
 
         let clone = interface.clone();
         tokio::task::spawn(async move {
             log::debug!("sensor subscriber started");
+            let mut resubscribe_attempts = 0;
+            let mut seq = 0;
+
             loop {
                 match sensors_subscriber.next().await {
                     Some(data) => {
-                        if let Err(e) = clone.sensor_tx.send(data.into())
-                            && clone.sensor_tx.receiver_count() > 0
+                        resubscribe_attempts = 0;
+                        clone.sensor_frames.fetch_add(1, Ordering::Relaxed);
+                        let states = match SensorsStates::try_from(data) {
+                            Ok(states) => states,
+                            Err(e) => {
+                                log::warn!("skipping malformed sensor frame: {}", e);
+                                continue;
+                            }
+                        };
+                        seq += 1;
+                        frame_tx.send_replace(Some(SensorFrame {
+                            seq,
+                            received_at: Instant::now(),
+                            states: states.clone(),
+                        }));
+                        if let Err(e) = sensor_tx.send(states)
+                            && sensor_tx.receiver_count() > 0
                         {
                             log::warn!("failed to send sensor data: {}", e);
                             break;
                         }
                     }
+                    None if resubscribe_attempts < MAX_RESUBSCRIBE_ATTEMPTS => {
+                        resubscribe_attempts += 1;
+                        log::warn!(
+                            "sensor subscriber stream ended, resubscribing ({}/{})",
+                            resubscribe_attempts,
+                            MAX_RESUBSCRIBE_ATTEMPTS
+                        );
+                        tokio::time::sleep(RESUBSCRIBE_BACKOFF * resubscribe_attempts as u32).await;
+
+                        match subscribe_sensors_topic(&node, &sensor_topic) {
+                            Ok(subscriber) => sensors_subscriber = subscriber,
+                            Err(e) => log::warn!("failed to resubscribe to sensors: {}", e),
+                        }
+                    }
                     None => {
-                        log::warn!("sensor subscriber stream ended");
+                        log::error!(
+                            "sensor subscriber stream ended after {} resubscribe attempts",
+                            MAX_RESUBSCRIBE_ATTEMPTS
+                        );
                         break;
                     }
                 }
             }
+
+            // drop every sender so pending and future receivers see `Closed` instead of hanging
+            if let Ok(mut shared_tx) = clone.sensor_tx.lock() {
+                shared_tx.take();
+            }
         });
 
         Ok(interface)
@@ -67,41 +170,132 @@ impl ROSInterface {
         Ok(())
     }
 
-    pub async fn get_map(&self) -> eyre::Result<GetMap::Response> {
-        let response = self
-            .get_map_client
-            .request(&GetMap::Request::default())?
-            .await?;
-        Ok(response)
+    pub fn stats(&self) -> InteractionStats {
+        InteractionStats {
+            get_map_calls: self.get_map_calls.load(Ordering::Relaxed),
+            move_calls: self.move_calls.load(Ordering::Relaxed),
+            reset_calls: self.reset_calls.load(Ordering::Relaxed),
+            sensor_frames: self.sensor_frames.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl MazeBackend for ROSInterface {
+    async fn get_map(&self) -> eyre::Result<RawMap> {
+        self.get_map_calls.fetch_add(1, Ordering::Relaxed);
+        let response = with_timeout(
+            "get_map",
+            self.request_timeout,
+            self.get_map_client.request(&GetMap::Request::default())?,
+        )
+        .await?;
+        Ok(RawMap {
+            occupancy_grid_flattened: response.occupancy_grid_flattened,
+            occupancy_grid_shape: response.occupancy_grid_shape,
+        })
     }
 
-    pub async fn move_cmd(&self, direction: MoveDirection) -> eyre::Result<MoveCmd::Response> {
-        let response = self
-            .move_client
-            .request(&MoveCmd::Request {
+    async fn move_cmd(&self, direction: MoveDirection) -> eyre::Result<bool> {
+        self.move_calls.fetch_add(1, Ordering::Relaxed);
+        let response = with_timeout(
+            "move_command",
+            self.request_timeout,
+            self.move_client.request(&MoveCmd::Request {
                 direction: direction.as_str().to_string(),
-            })?
-            .await?;
-        Ok(response)
+            })?,
+        )
+        .await?;
+
+        if !self.simulated_latency.is_zero() {
+            tokio::time::sleep(self.simulated_latency).await;
+        }
+        Ok(response.success)
+    }
+
+    async fn reset(&self) -> eyre::Result<()> {
+        self.reset_calls.fetch_add(1, Ordering::Relaxed);
+        with_timeout(
+            "reset",
+            self.request_timeout,
+            self.reset_client.request(&Reset::Request {
+                is_random: false,
+                map_name: String::new(),
+            })?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// publishes `{"step": .., "known_cells": ..}` as a string message; a failed publish is only
+    /// logged, monitoring must never fail a run
+    fn publish_progress(&self, step: usize, known_cells: usize) {
+        let Some(publisher) = &self.progress_publisher else {
+            return;
+        };
+
+        let msg = StringMsg {
+            data: serde_json::json!({ "step": step, "known_cells": known_cells }).to_string(),
+        };
+        let published = publisher
+            .lock()
+            .map_err(|_| eyre::eyre!("progress publisher mutex poisoned"))
+            .and_then(|publisher| Ok(publisher.publish(&msg)?));
+        if let Err(e) = published {
+            log::warn!("failed to publish progress: {}", e);
+        }
+    }
+
+    fn subscribe_sensors(&self) -> broadcast::Receiver<SensorsStates> {
+        match self
+            .sensor_tx
+            .lock()
+            .ok()
+            .as_ref()
+            .and_then(|tx| tx.as_ref())
+        {
+            Some(tx) => tx.subscribe(),
+            // stream is gone for good, hand out a receiver that is already closed
+            None => broadcast::channel(1).1,
+        }
     }
 
-    pub async fn reset(&self, is_random: bool, map_name: String) -> eyre::Result<Reset::Response> {
-        let response = self
-            .reset_client
-            .request(&Reset::Request {
-                is_random,
-                map_name,
-            })?
-            .await?;
-        Ok(response)
+    fn watch_sensors(&self) -> watch::Receiver<Option<SensorFrame>> {
+        self.sensor_watch.clone()
     }
 
-    pub fn subscribe_sensors(&self) -> broadcast::Receiver<SensorsStates> {
-        self.sensor_tx.subscribe()
+    fn latest_sensors(&self) -> Option<SensorFrame> {
+        self.sensor_watch.borrow().clone()
     }
 }
 
 // Helper functions
+/// awaits a service response, failing with the service name once `timeout` passes so a dead or
+/// hung simulator surfaces as an error instead of a solver that never returns
+async fn with_timeout<T>(
+    service: &str,
+    timeout: Duration,
+    response: impl Future<Output = R2RResult<T>>,
+) -> eyre::Result<T> {
+    match tokio::time::timeout(timeout, response).await {
+        Ok(response) => Ok(response?),
+        Err(_) => eyre::bail!("{} timed out after {:?}", service, timeout),
+    }
+}
+
+fn lock_node(node: &Mutex<Node>) -> eyre::Result<std::sync::MutexGuard<'_, Node>> {
+    node.lock()
+        .map_err(|_| eyre::eyre!("ros node mutex poisoned"))
+}
+
+fn subscribe_sensors_topic(
+    node: &Mutex<Node>,
+    topic: &str,
+) -> eyre::Result<BoxStream<'static, RobotSensors>> {
+    let subscriber =
+        lock_node(node)?.subscribe::<RobotSensors>(topic, QosProfile::sensor_data())?;
+    Ok(subscriber.boxed())
+}
+
 fn create_client<T: WrappedServiceTypeSupport + 'static>(
     node: &mut Node,
     service_name: &str,
@@ -112,3 +306,26 @@ fn create_client<T: WrappedServiceTypeSupport + 'static>(
 async fn wait_client<T: WrappedServiceTypeSupport + 'static>(client: &Client<T>) -> R2RResult<()> {
     r2r::Node::is_available(client)?.await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn with_timeout_names_the_service_that_hung() {
+        let never = std::future::pending::<R2RResult<()>>();
+
+        let err = with_timeout("move_command", Duration::from_millis(10), never)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "move_command timed out after 10ms");
+    }
+
+    #[tokio::test]
+    async fn with_timeout_passes_a_prompt_response_through() {
+        let response = with_timeout("get_map", Duration::from_secs(1), async { Ok(7) }).await;
+
+        assert_eq!(response.unwrap(), 7);
+    }
+}
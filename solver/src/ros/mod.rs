@@ -1,4 +1,6 @@
+mod config;
 mod interface;
 pub mod types;
 
-pub use interface::ROSInterface;
+pub use config::{DEFAULT_NODE_NAME, RosConfig};
+pub use interface::{InteractionStats, ROSInterface};
@@ -1,32 +1,112 @@
 use r2r::cg_interfaces::msg::RobotSensors;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// serialized under the same names `as_str` sends to the simulator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MoveDirection {
     Up,
     Down,
     Left,
     Right,
+    /// diagonals only appear in paths planned with diagonal movement enabled
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
 impl MoveDirection {
+    /// the four cardinal directions, the only ones exploration and 4-connected planning use
+    pub const fn all() -> [Self; 4] {
+        [Self::Up, Self::Down, Self::Left, Self::Right]
+    }
+
+    pub const fn diagonals() -> [Self; 4] {
+        [Self::UpLeft, Self::UpRight, Self::DownLeft, Self::DownRight]
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Up => "up",
             Self::Down => "down",
             Self::Left => "left",
             Self::Right => "right",
+            Self::UpLeft => "up_left",
+            Self::UpRight => "up_right",
+            Self::DownLeft => "down_left",
+            Self::DownRight => "down_right",
+        }
+    }
+
+    /// (row, col) change one move in this direction makes
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::Down => (1, 0),
+            Self::Left => (0, -1),
+            Self::Right => (0, 1),
+            Self::UpLeft => (-1, -1),
+            Self::UpRight => (-1, 1),
+            Self::DownLeft => (1, -1),
+            Self::DownRight => (1, 1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        Self::diagonals().contains(self)
+    }
+
+    /// quarter turn counter-clockwise; diagonals are returned unchanged
+    pub fn rotate_left(&self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Left => Self::Down,
+            Self::Down => Self::Right,
+            Self::Right => Self::Up,
+            diagonal => *diagonal,
+        }
+    }
+
+    /// quarter turn clockwise; diagonals are returned unchanged
+    pub fn rotate_right(&self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+            diagonal => *diagonal,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::UpLeft => Self::DownRight,
+            Self::UpRight => Self::DownLeft,
+            Self::DownLeft => Self::UpRight,
+            Self::DownRight => Self::UpLeft,
         }
     }
 }
 
-impl From<&str> for MoveDirection {
-    fn from(s: &str) -> Self {
-        match s {
-            "up" => Self::Up,
-            "down" => Self::Down,
-            "left" => Self::Left,
-            "right" => Self::Right,
-            _ => panic!("Invalid move direction: {}", s),
+impl TryFrom<&str> for MoveDirection {
+    type Error = eyre::Error;
+
+    fn try_from(s: &str) -> eyre::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "up_left" => Ok(Self::UpLeft),
+            "up_right" => Ok(Self::UpRight),
+            "down_left" => Ok(Self::DownLeft),
+            "down_right" => Ok(Self::DownRight),
+            _ => eyre::bail!("invalid move direction: {:?}", s),
         }
     }
 }
@@ -38,13 +118,15 @@ pub enum SensorState {
     Free,
 }
 
-impl From<&str> for SensorState {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for SensorState {
+    type Error = eyre::Error;
+
+    fn try_from(s: &str) -> eyre::Result<Self> {
         match s.to_lowercase().as_str() {
-            "b" => Self::Blocked,
-            "t" => Self::Target,
-            "f" => Self::Free,
-            _ => panic!("Invalid sensor state: {}", s),
+            "b" => Ok(Self::Blocked),
+            "t" => Ok(Self::Target),
+            "f" => Ok(Self::Free),
+            _ => eyre::bail!("invalid sensor state: {:?}", s),
         }
     }
 }
@@ -61,17 +143,152 @@ pub struct SensorsStates {
     pub down_right: SensorState,
 }
 
-impl From<RobotSensors> for SensorsStates {
-    fn from(sensors: RobotSensors) -> Self {
+impl SensorsStates {
+    /// every one of the eight readings set to `state`
+    pub fn uniform(state: SensorState) -> Self {
         Self {
-            up: SensorState::from(sensors.up.as_str()),
-            down: SensorState::from(sensors.down.as_str()),
-            left: SensorState::from(sensors.left.as_str()),
-            right: SensorState::from(sensors.right.as_str()),
-            up_left: SensorState::from(sensors.up_left.as_str()),
-            up_right: SensorState::from(sensors.up_right.as_str()),
-            down_left: SensorState::from(sensors.down_left.as_str()),
-            down_right: SensorState::from(sensors.down_right.as_str()),
+            up: state,
+            down: state,
+            left: state,
+            right: state,
+            up_left: state,
+            up_right: state,
+            down_left: state,
+            down_right: state,
+        }
+    }
+
+    pub fn get(&self, direction: MoveDirection) -> SensorState {
+        match direction {
+            MoveDirection::Up => self.up,
+            MoveDirection::Down => self.down,
+            MoveDirection::Left => self.left,
+            MoveDirection::Right => self.right,
+            MoveDirection::UpLeft => self.up_left,
+            MoveDirection::UpRight => self.up_right,
+            MoveDirection::DownLeft => self.down_left,
+            MoveDirection::DownRight => self.down_right,
+        }
+    }
+
+    /// every reading paired with its (row, col) offset from the robot
+    pub fn readings(&self) -> [((isize, isize), SensorState); 8] {
+        [
+            ((-1, 0), self.up),
+            ((1, 0), self.down),
+            ((0, -1), self.left),
+            ((0, 1), self.right),
+            ((-1, -1), self.up_left),
+            ((-1, 1), self.up_right),
+            ((1, -1), self.down_left),
+            ((1, 1), self.down_right),
+        ]
+    }
+
+    /// 3×3 block with the robot as `R` in the middle, `.` free, `#` blocked and `T` target
+    pub fn render_grid(&self) -> String {
+        let symbol = |state: SensorState| match state {
+            SensorState::Free => '.',
+            SensorState::Blocked => '#',
+            SensorState::Target => 'T',
+        };
+
+        [
+            [self.up_left, self.up, self.up_right].map(symbol),
+            [symbol(self.left), 'R', symbol(self.right)],
+            [self.down_left, self.down, self.down_right].map(symbol),
+        ]
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    pub fn set(&mut self, direction: MoveDirection, state: SensorState) {
+        match direction {
+            MoveDirection::Up => self.up = state,
+            MoveDirection::Down => self.down = state,
+            MoveDirection::Left => self.left = state,
+            MoveDirection::Right => self.right = state,
+            MoveDirection::UpLeft => self.up_left = state,
+            MoveDirection::UpRight => self.up_right = state,
+            MoveDirection::DownLeft => self.down_left = state,
+            MoveDirection::DownRight => self.down_right = state,
+        }
+    }
+}
+
+impl TryFrom<RobotSensors> for SensorsStates {
+    type Error = eyre::Error;
+
+    fn try_from(sensors: RobotSensors) -> eyre::Result<Self> {
+        Ok(Self {
+            up: sensors.up.as_str().try_into()?,
+            down: sensors.down.as_str().try_into()?,
+            left: sensors.left.as_str().try_into()?,
+            right: sensors.right.as_str().try_into()?,
+            up_left: sensors.up_left.as_str().try_into()?,
+            up_right: sensors.up_right.as_str().try_into()?,
+            down_left: sensors.down_left.as_str().try_into()?,
+            down_right: sensors.down_right.as_str().try_into()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opposite_reverses_each_cardinal_direction() {
+        use MoveDirection::{Down, Left, Right, Up};
+
+        assert_eq!(Up.opposite(), Down);
+        assert_eq!(Down.opposite(), Up);
+        assert_eq!(Left.opposite(), Right);
+        assert_eq!(Right.opposite(), Left);
+        for direction in MoveDirection::all() {
+            assert_eq!(direction.opposite().opposite(), direction);
+        }
+    }
+
+    #[test]
+    fn move_direction_parses_any_case_and_rejects_the_rest() {
+        assert_eq!(MoveDirection::try_from("up").unwrap(), MoveDirection::Up);
+        assert_eq!(MoveDirection::try_from("UP").unwrap(), MoveDirection::Up);
+        assert_eq!(
+            MoveDirection::try_from("Down_Left").unwrap(),
+            MoveDirection::DownLeft
+        );
+        for direction in MoveDirection::all()
+            .into_iter()
+            .chain(MoveDirection::diagonals())
+        {
+            assert_eq!(
+                MoveDirection::try_from(direction.as_str()).unwrap(),
+                direction
+            );
+        }
+
+        for bad in ["", " up", "north", "upleft"] {
+            let err = MoveDirection::try_from(bad).unwrap_err();
+            assert!(
+                err.to_string().contains("invalid move direction"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn sensor_state_parses_any_case_and_rejects_the_rest() {
+        assert_eq!(SensorState::try_from("b").unwrap(), SensorState::Blocked);
+        assert_eq!(SensorState::try_from("T").unwrap(), SensorState::Target);
+        assert_eq!(SensorState::try_from("F").unwrap(), SensorState::Free);
+
+        for bad in ["", "x", "free", "r"] {
+            let err = SensorState::try_from(bad).unwrap_err();
+            assert!(err.to_string().contains("invalid sensor state"), "{}", err);
         }
     }
 }
@@ -1,53 +1,106 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use clap::ValueEnum;
+use tokio::sync::watch;
+
 use crate::{
+    backend::{MOVE_RETRY_BACKOFF, MazeBackend, SensorFrame, sensors_since},
     maze::{BoundedMaze, Cell, Position, UnboundedMaze, UnboundedPosition},
-    ros::ROSInterface,
 };
 
+use super::{return_home::drive_home, stray::StrayGuard};
 use crate::algorithms::{
-    exploration::ExplorationAlgorithm, pathfinding::PathResult, pathfinding::PathfindingAlgorithm,
+    exploration::ExplorationAlgorithm,
+    pathfinding::{PathResult, PathfindingAlgorithm},
 };
-use crate::ros::types::{MoveDirection, SensorsStates};
+use crate::ros::types::{MoveDirection, SensorState, SensorsStates};
+
+/// runtime knobs shared by every blind solver regardless of algorithm choice
+#[derive(Debug, Clone)]
+pub struct BlindConfig {
+    /// delay between moves
+    pub delay: Duration,
+    /// how long to keep waiting for the first sensor frame before giving up
+    pub sensor_timeout: Duration,
+    /// reset the maze before executing the optimal path, otherwise plan from where exploration ended
+    pub reset_before_exec: bool,
+    /// largest explored region (n×n) tolerated before exploration is considered runaway
+    pub max_extent: Option<usize>,
+    /// drive the executed path back to its start after reaching the target
+    pub return_home: bool,
+    /// treat a failed exploration move as a misreported wall instead of aborting
+    pub tolerate_blocked_moves: bool,
+    /// what unexplored cells become when planning over the explored region
+    pub plan_unknown: PlanUnknown,
+    /// follow a target that relocates instead of trusting where it was first spotted
+    pub track_target: bool,
+    /// write the explored map here after exploration, for `load_explored` to pick up later
+    pub save_explored: Option<PathBuf>,
+    /// skip exploration and plan over a map saved by `save_explored`
+    pub load_explored: Option<PathBuf>,
+    /// check the robot's real cell after every execution move and abort once it leaves the path
+    pub abort_on_stray: bool,
+    /// log an ascii rendering of the explored map once exploration finishes
+    pub dump_map: bool,
+    /// extra attempts for a move the simulator refuses before it counts as failed
+    pub move_retries: usize,
+    /// exploration moves allowed before the run is aborted as a likely infinite loop
+    pub max_steps: usize,
+}
+
+/// how the blind-to-bounded conversion treats cells exploration never saw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum PlanUnknown {
+    /// pessimistic: unexplored cells are walls, so every planned move is known to be open
+    #[default]
+    Wall,
+    /// optimistic: unexplored cells are open, execution replans whenever a move turns out blocked
+    Open,
+}
+
+/// consecutive failed moves tolerated before exploration gives up
+const MAX_CONSECUTIVE_FAILED_MOVES: usize = 8;
 
 /// explores maze using only sensor data with unbounded coordinates
 pub struct BlindSolver<E: ExplorationAlgorithm, P: PathfindingAlgorithm> {
     exploration: E,
     pathfinding: P,
-    delay: Duration,
+    config: BlindConfig,
 }
 
 impl<E: ExplorationAlgorithm, P: PathfindingAlgorithm> BlindSolver<E, P> {
-    pub fn new(exploration: E, pathfinding: P, delay_ms: u64) -> Self {
+    pub fn new(exploration: E, pathfinding: P, config: BlindConfig) -> Self {
         Self {
             exploration,
             pathfinding,
-            delay: Duration::from_millis(delay_ms),
+            config,
         }
     }
 
-    pub async fn solve(&mut self, ros: Arc<ROSInterface>) -> eyre::Result<PathResult> {
+    pub async fn solve<B: MazeBackend>(&mut self, ros: Arc<B>) -> eyre::Result<PathResult> {
+        if let Some(path) = self.config.load_explored.clone() {
+            return self.solve_from_explored(ros, &path).await;
+        }
+
         log::debug!("starting blind exploration");
         log::debug!("using unbounded coordinate system");
 
         self.exploration.reset();
         let mut maze = UnboundedMaze::new();
-        let mut sensor_rx = ros.subscribe_sensors();
+        let mut sensor_rx = ros.watch_sensors();
 
         log::debug!("waiting for sensors");
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-        // drain any stale sensor messages from before reset
-        while !sensor_rx.is_empty() {
-            let _ = sensor_rx.recv().await;
-        }
 
-        let initial_sensors = sensor_rx.recv().await?;
+        // frames from before the reset are stale, only a later one shows the start
+        let initial_sensors =
+            Self::wait_for_sensors(&mut sensor_rx, Instant::now(), self.config.sensor_timeout)
+                .await?;
 
-        let mut current_pos = UnboundedPosition::new(0, 0);
-        maze.set(current_pos, Cell::Robot);
+        let exploration_start = UnboundedPosition::new(0, 0);
+        let mut current_pos = exploration_start;
         maze.update_from_sensors(current_pos, &initial_sensors);
 
         log::info!("starting at origin");
@@ -58,47 +111,127 @@ impl<E: ExplorationAlgorithm, P: PathfindingAlgorithm> BlindSolver<E, P> {
         let total_start = Instant::now();
         let mut total_planning_time = Duration::default();
 
-        log::info!("phase 1: exploring maze with {}", self.exploration.name());
-
-        let (target_position, exploration_steps) = self
-            .explore_phase(
-                &ros,
-                &mut maze,
-                &mut sensor_rx,
-                &mut sensor_cache,
-                &mut current_pos,
-                &mut total_planning_time,
-            )
-            .await?;
+        // a target next to the start needs no exploration, the plan is the single step onto it
+        let (target_position, exploration_steps) =
+            match Self::adjacent_target_direction(&initial_sensors) {
+                Some(direction) => {
+                    log::info!(
+                        "target is adjacent to start ({:?}), skipping exploration",
+                        direction
+                    );
+                    (current_pos.move_in_direction(direction), 0)
+                }
+                None => {
+                    log::info!(
+                        phase = "exploration",
+                        algorithm = self.exploration.name();
+                        "phase 1: exploring maze with {}",
+                        self.exploration.name()
+                    );
+
+                    self.explore_phase(
+                        &ros,
+                        &mut maze,
+                        &mut sensor_rx,
+                        &mut sensor_cache,
+                        &mut current_pos,
+                        &mut total_planning_time,
+                    )
+                    .await?
+                }
+            };
 
         log::info!(
-            "exploration complete: found target at ({}, {}) in {} steps",
-            target_position.row,
-            target_position.col,
+            "exploration complete: found target at {} in {} steps",
+            target_position,
             exploration_steps
         );
 
+        let stats = maze.stats();
+        log::debug!(
+            walkable = stats.walkable,
+            blocked = stats.blocked,
+            fill_ratio = stats.fill_ratio;
+            "explored {} walkable and {} blocked cells ({:.0}% of bounding box)",
+            stats.walkable,
+            stats.blocked,
+            stats.fill_ratio * 100.0
+        );
+        Self::warn_on_islands(&maze);
+
+        if self.config.dump_map {
+            log::info!("explored map:\n{}", maze.render_ascii());
+        }
+
+        if let Some(path) = &self.config.save_explored {
+            let (explored, _, _) =
+                self.convert_to_bounded(&maze, exploration_start, target_position)?;
+            explored.save(path)?;
+            log::info!("saved explored map to {}", path.display());
+        }
+
         log::info!(
+            phase = "planning",
+            algorithm = self.pathfinding.name();
             "phase 2: planning optimal path with {}",
             self.pathfinding.name()
         );
 
+        // without a reset the robot stays where exploration left it
+        let plan_start = if self.config.reset_before_exec {
+            exploration_start
+        } else {
+            current_pos
+        };
+
         let planning_start = Instant::now();
-        let (bounded_maze, start, target) = self.convert_to_bounded(&maze, target_position)?;
-        let optimal_path = self
-            .pathfinding
-            .find_path(&bounded_maze, start, target)
+        let (mut bounded_maze, start, target) =
+            self.convert_to_bounded(&maze, plan_start, target_position)?;
+        self.apply_plan_unknown(&mut bounded_maze);
+        let outcome = self.pathfinding.search(&bounded_maze, start, target);
+        let optimal_path = outcome
+            .path
             .ok_or_else(|| eyre::eyre!("no path found to target"))?;
         total_planning_time += planning_start.elapsed();
 
         log::info!("planned optimal path: {} steps", optimal_path.len());
 
-        log::info!("resetting maze and executing optimal path");
+        if self.config.reset_before_exec {
+            log::info!("resetting maze and executing optimal path");
 
-        ros.reset(false, String::new()).await?;
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            ros.reset().await?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        } else {
+            log::info!(
+                "executing optimal path from {} without resetting",
+                current_pos
+            );
+        }
 
-        let execution_steps = self.execute_path(&ros, &optimal_path).await?;
+        let executed_path = self
+            .execute_path(
+                &ros,
+                &mut bounded_maze,
+                start,
+                target,
+                optimal_path,
+                &mut total_planning_time,
+            )
+            .await?;
+        let mut execution_steps = executed_path.len();
+
+        if self.config.return_home {
+            execution_steps += drive_home(
+                ros.as_ref(),
+                &executed_path,
+                self.config.delay,
+                self.config.move_retries,
+            )
+            .await?;
+            self.verify_home(ros.as_ref(), &sensor_cache, plan_start)
+                .await?;
+            log::info!("back home at {}", plan_start);
+        }
 
         let total_time = total_start.elapsed();
         let execution_time = total_time - total_planning_time;
@@ -114,54 +247,151 @@ impl<E: ExplorationAlgorithm, P: PathfindingAlgorithm> BlindSolver<E, P> {
             exploration_steps + execution_steps,
             total_planning_time,
             execution_time,
-        ))
+        )
+        .with_path(&bounded_maze, start, &executed_path)
+        .with_nodes(outcome.nodes_expanded))
+    }
+
+    /// plans over a map saved by `--save-explored` and executes it after a reset, no exploration
+    async fn solve_from_explored(
+        &mut self,
+        ros: Arc<impl MazeBackend>,
+        path: &Path,
+    ) -> eyre::Result<PathResult> {
+        let mut maze = BoundedMaze::from_file(path)?;
+        let start = maze
+            .find_robot()
+            .ok_or_else(|| eyre::eyre!("{}: no robot ('r') in explored map", path.display()))?;
+        let target = maze
+            .find_target()
+            .ok_or_else(|| eyre::eyre!("{}: no target ('t') in explored map", path.display()))?;
+
+        log::info!(
+            "loaded {}x{} explored map from {}, skipping exploration",
+            maze.height(),
+            maze.width(),
+            path.display()
+        );
+
+        let total_start = Instant::now();
+        let mut total_planning_time = Duration::default();
+
+        log::info!(
+            phase = "planning",
+            algorithm = self.pathfinding.name();
+            "planning optimal path with {}",
+            self.pathfinding.name()
+        );
+
+        let planning_start = Instant::now();
+        self.apply_plan_unknown(&mut maze);
+        let outcome = self.pathfinding.search(&maze, start, target);
+        let optimal_path = outcome
+            .path
+            .ok_or_else(|| eyre::eyre!("no path found to target"))?;
+        total_planning_time += planning_start.elapsed();
+
+        log::info!("planned optimal path: {} steps", optimal_path.len());
+        log::info!("resetting maze and executing optimal path");
+
+        ros.reset().await?;
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+        let executed_path = self
+            .execute_path(
+                &ros,
+                &mut maze,
+                start,
+                target,
+                optimal_path,
+                &mut total_planning_time,
+            )
+            .await?;
+        let mut execution_steps = executed_path.len();
+
+        if self.config.return_home {
+            execution_steps += drive_home(
+                ros.as_ref(),
+                &executed_path,
+                self.config.delay,
+                self.config.move_retries,
+            )
+            .await?;
+            log::info!(
+                "back home at {} (no recorded sensors to verify against)",
+                start
+            );
+        }
+
+        let execution_time = total_start.elapsed() - total_planning_time;
+
+        Ok(
+            PathResult::new(execution_steps, total_planning_time, execution_time)
+                .with_path(&maze, start, &executed_path)
+                .with_nodes(outcome.nodes_expanded),
+        )
     }
 
     async fn explore_phase(
         &mut self,
-        ros: &Arc<ROSInterface>,
+        ros: &Arc<impl MazeBackend>,
         maze: &mut UnboundedMaze,
-        sensor_rx: &mut tokio::sync::broadcast::Receiver<SensorsStates>,
+        sensor_rx: &mut watch::Receiver<Option<SensorFrame>>,
         sensor_cache: &mut HashMap<UnboundedPosition, SensorsStates>,
         current_pos: &mut UnboundedPosition,
         total_planning_time: &mut Duration,
     ) -> eyre::Result<(UnboundedPosition, usize)> {
         let mut target_pos: Option<UnboundedPosition> = None;
         let mut steps = 0;
+        let mut consecutive_failures = 0;
+        let mut moved_at = Instant::now();
 
         loop {
-            let sensors = if let Some(cached_sensors) = sensor_cache.get(current_pos) {
-                log::debug!(
-                    "cache hit! using cached sensors for ({}, {})",
-                    current_pos.row,
-                    current_pos.col
-                );
+            // a moving target makes cached readings stale, so tracking always reads fresh ones
+            let cached = sensor_cache
+                .get(current_pos)
+                .filter(|_| !self.config.track_target);
+            let sensors = if let Some(cached_sensors) = cached {
+                log::debug!("cache hit! using cached sensors for {}", current_pos);
                 cached_sensors.clone()
             } else {
-                // drain sensors
-                while !sensor_rx.is_empty() {
-                    let _ = sensor_rx.recv().await;
-                }
-
-                let fresh_sensors = sensor_rx.recv().await?;
-                log::trace!(
-                    "fresh sensors for ({}, {})",
-                    current_pos.row,
-                    current_pos.col
-                );
-                sensor_cache.insert(*current_pos, fresh_sensors.clone());
-                fresh_sensors
+                let frame = sensors_since(sensor_rx, moved_at).await?;
+                log::trace!("fresh sensors for {} (frame {})", current_pos, frame.seq);
+                sensor_cache.insert(*current_pos, frame.states.clone());
+                frame.states
             };
+            log::trace!("sensors at {}:\n{}", current_pos, sensors.render_grid());
             maze.update_from_sensors(*current_pos, &sensors);
 
+            if let Some(max_extent) = self.config.max_extent {
+                Self::check_extent(maze, max_extent)?;
+            }
+
             // detect target but don't stop exploring
-            if target_pos.is_none()
-                && let Some(pos) = Self::detect_target_in_sensors(*current_pos, &sensors)
-            {
-                log::info!("target spotted at ({}, {})", pos.row, pos.col);
-                target_pos = Some(pos);
+            match (
+                Self::detect_target_in_sensors(*current_pos, &sensors),
+                target_pos,
+            ) {
+                (Some(pos), None) => {
+                    log::info!("target spotted at {}", pos);
+                    target_pos = Some(pos);
+                }
+                (Some(pos), Some(previous)) if self.config.track_target && pos != previous => {
+                    log::info!("target moved from {} to {}", previous, pos);
+                    target_pos = Some(pos);
+                }
+                (None, Some(previous))
+                    if self.config.track_target
+                        && Self::reading_at(*current_pos, previous, &sensors)
+                            .is_some_and(|state| state != SensorState::Target) =>
+                {
+                    log::warn!("target no longer at {}, searching again", previous);
+                    target_pos = None;
+                }
+                _ => {}
             }
 
+            self.exploration.set_target_hint(target_pos);
             let planning_start = Instant::now();
             let next_move = self.exploration.next_move(*current_pos, &sensors, maze)?;
             *total_planning_time += planning_start.elapsed();
@@ -173,31 +403,74 @@ impl<E: ExplorationAlgorithm, P: PathfindingAlgorithm> BlindSolver<E, P> {
 
             let direction = next_move.unwrap();
 
-            if self.delay.as_millis() > 0 {
-                tokio::time::sleep(self.delay).await;
+            if let Some(rationale) = self.exploration.move_rationale() {
+                log::trace!("{}", rationale);
+            }
+
+            if self.config.delay.as_millis() > 0 {
+                tokio::time::sleep(self.config.delay).await;
             }
 
             log::debug!(
-                "exploration step {}: {:?} from ({}, {})",
+                phase = "exploration",
+                step = steps + 1,
+                direction:? = direction,
+                row = current_pos.row,
+                col = current_pos.col;
+                "exploration step {}: {:?} from {}",
                 steps + 1,
                 direction,
-                current_pos.row,
-                current_pos.col
+                current_pos
             );
 
-            let response = ros.move_cmd(direction).await?;
-            if !response.success {
-                eyre::bail!("move failed at step {}: {:?}", steps + 1, direction);
+            let moved = ros
+                .move_cmd_retry(direction, self.config.move_retries, MOVE_RETRY_BACKOFF)
+                .await?;
+            moved_at = Instant::now();
+            if !moved {
+                if !self.config.tolerate_blocked_moves {
+                    eyre::bail!("move failed at step {}: {:?}", steps + 1, direction);
+                }
+
+                consecutive_failures += 1;
+                if consecutive_failures > MAX_CONSECUTIVE_FAILED_MOVES {
+                    eyre::bail!(
+                        "{} consecutive failed moves at {}, giving up",
+                        consecutive_failures,
+                        current_pos
+                    );
+                }
+
+                let blocked = current_pos.move_in_direction(direction);
+                log::warn!(
+                    "move {:?} from {} failed, treating {} as a wall",
+                    direction,
+                    current_pos,
+                    blocked
+                );
+
+                // patch the cached reading too, otherwise the next sensor update reopens the cell
+                if let Some(cached) = sensor_cache.get_mut(current_pos) {
+                    cached.set(direction, SensorState::Blocked);
+                }
+                maze.set(blocked, Cell::Blocked);
+                self.exploration.on_move_failed(direction, *current_pos);
+                continue;
             }
+            consecutive_failures = 0;
 
-            maze.set(*current_pos, Cell::Free);
             *current_pos = current_pos.move_in_direction(direction);
-            maze.set(*current_pos, Cell::Robot);
+            maze.place_robot(*current_pos);
 
             steps += 1;
+            ros.publish_progress(steps, maze.known_cells());
 
-            if steps > 10_000 {
-                eyre::bail!("too many steps ({}) - possible infinite loop", steps);
+            if steps > self.config.max_steps {
+                eyre::bail!(
+                    "too many steps ({}, limit {}) - possible infinite loop",
+                    steps,
+                    self.config.max_steps
+                );
             }
         }
 
@@ -206,107 +479,335 @@ impl<E: ExplorationAlgorithm, P: PathfindingAlgorithm> BlindSolver<E, P> {
             .map(|pos| (pos, steps))
     }
 
+    fn check_extent(maze: &UnboundedMaze, max_extent: usize) -> eyre::Result<()> {
+        let Some((min_row, max_row, min_col, max_col)) = maze.get_bounds() else {
+            return Ok(());
+        };
+
+        let height = (max_row - min_row + 1) as usize;
+        let width = (max_col - min_col + 1) as usize;
+
+        if height > max_extent || width > max_extent {
+            eyre::bail!(
+                "explored region {}x{} exceeds max extent {}x{} (rows {}..={}, cols {}..={})",
+                height,
+                width,
+                max_extent,
+                max_extent,
+                min_row,
+                max_row,
+                min_col,
+                max_col
+            );
+        }
+
+        Ok(())
+    }
+
     fn convert_to_bounded(
         &self,
         unbounded: &UnboundedMaze,
+        start: UnboundedPosition,
         target: UnboundedPosition,
     ) -> eyre::Result<(BoundedMaze, Position, Position)> {
         let (min_row, max_row, min_col, max_col) = unbounded
-            .get_bounds()
+            .trimmed_bounds()
             .ok_or_else(|| eyre::eyre!("empty maze"))?;
 
         let height = (max_row - min_row + 1) as usize;
         let width = (max_col - min_col + 1) as usize;
 
-        let mut grid_data = vec!["u".to_string(); height * width];
+        let origin = UnboundedPosition::new(min_row, min_col);
+        let mut grid_data = vec![Cell::Unknown.symbol().to_string(); height * width];
 
         for row in min_row..=max_row {
             for col in min_col..=max_col {
                 let pos = UnboundedPosition::new(row, col);
-                let cell = unbounded.get(pos);
-                let grid_row = (row - min_row) as usize;
-                let grid_col = (col - min_col) as usize;
-                let idx = grid_row * width + grid_col;
-
-                grid_data[idx] = match cell {
-                    Cell::Free => "f".to_string(),
-                    Cell::Blocked => "b".to_string(),
-                    Cell::Target => "t".to_string(),
-                    Cell::Robot => "r".to_string(),
-                    Cell::Unknown => "u".to_string(),
-                };
+                if let Some(cell) = pos
+                    .to_bounded(origin)
+                    .and_then(|grid_pos| grid_data.get_mut(grid_pos.to_index(width)))
+                {
+                    *cell = unbounded.get(pos).symbol().to_string();
+                }
             }
         }
 
-        let bounded = BoundedMaze::from_flattened(grid_data, vec![height as u8, width as u8])?;
+        let mut bounded = BoundedMaze::from_flattened(grid_data, vec![height as u8, width as u8])?;
+
+        let start_pos = start
+            .to_bounded(origin)
+            .ok_or_else(|| eyre::eyre!("plan start {} lies outside the explored map", start))?;
+        let target_pos = target
+            .to_bounded(origin)
+            .ok_or_else(|| eyre::eyre!("target {} lies outside the explored map", target))?;
+
+        // the robot is marked where the plan starts, not wherever exploration left it
+        if let Some(robot) = bounded.find_robot() {
+            bounded.set(robot, Cell::Free);
+        }
+        bounded.set(start_pos, Cell::Robot);
+
+        Ok((bounded, start_pos, target_pos))
+    }
+
+    /// a fully sensed walkable pocket the robot's region never reached points at bad sensor data
+    /// rather than maze topology; unsealed pockets are just unexplored, e.g. diagonal sightings
+    fn warn_on_islands(maze: &UnboundedMaze) {
+        let components = maze.connected_components();
+        log::debug!(
+            "explored map has {} walkable components, sizes {:?}",
+            components.len(),
+            components.iter().map(|c| c.size).collect::<Vec<_>>()
+        );
+
+        let islands: Vec<usize> = components
+            .iter()
+            .filter(|c| c.sealed && !c.has_robot)
+            .map(|c| c.size)
+            .collect();
+        if !islands.is_empty() {
+            log::warn!(
+                "explored map has {} sealed islands cut off from the robot (sizes {:?}), \
+                 likely a sensor error",
+                islands.len(),
+                islands
+            );
+        }
+    }
+
+    /// resolves cells exploration never saw according to `plan_unknown`
+    fn apply_plan_unknown(&self, maze: &mut BoundedMaze) {
+        let unknown: Vec<Position> = (0..maze.height() * maze.width())
+            .map(|idx| Position::from_index(idx, maze.width()))
+            .filter(|pos| maze.get(*pos) == Some(Cell::Unknown))
+            .collect();
+
+        if unknown.is_empty() {
+            return;
+        }
 
-        let start = Position::new((0 - min_row) as usize, (0 - min_col) as usize);
-        let target_pos = Position::new(
-            (target.row - min_row) as usize,
-            (target.col - min_col) as usize,
+        log::warn!(
+            "planning over {} unexplored cells treated as {}",
+            unknown.len(),
+            match self.config.plan_unknown {
+                PlanUnknown::Wall => "walls",
+                PlanUnknown::Open => "open",
+            }
         );
 
-        Ok((bounded, start, target_pos))
+        if self.config.plan_unknown == PlanUnknown::Open {
+            for pos in unknown {
+                maze.set(pos, Cell::Free);
+            }
+        }
     }
 
+    /// drives `path` and returns the moves actually taken, which differ from `path` when an
+    /// optimistic plan hit an unexplored wall and had to be replanned
     async fn execute_path(
         &self,
-        ros: &Arc<ROSInterface>,
-        path: &[MoveDirection],
-    ) -> eyre::Result<usize> {
-        let mut sensor_rx = ros.subscribe_sensors();
+        ros: &Arc<impl MazeBackend>,
+        maze: &mut BoundedMaze,
+        mut position: Position,
+        target: Position,
+        mut path: Vec<MoveDirection>,
+        total_planning_time: &mut Duration,
+    ) -> eyre::Result<Vec<MoveDirection>> {
+        let mut sensor_rx = ros.watch_sensors();
 
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-        // drain sensors
-        while !sensor_rx.is_empty() {
-            let _ = sensor_rx.recv().await;
-        }
+        let mut moved_at = Instant::now();
+        sensors_since(&mut sensor_rx, moved_at).await?;
 
-        sensor_rx.recv().await?;
+        let stray_guard = if self.config.abort_on_stray {
+            Some(StrayGuard::new(ros.as_ref(), position).await?)
+        } else {
+            None
+        };
+        let mut executed = Vec::with_capacity(path.len());
+        let mut next = 0;
 
-        for (i, direction) in path.iter().enumerate() {
-            if self.delay.as_millis() > 0 {
-                tokio::time::sleep(self.delay).await;
+        while let Some(&direction) = path.get(next) {
+            if self.config.delay.as_millis() > 0 {
+                tokio::time::sleep(self.config.delay).await;
             }
 
-            log::debug!("executing step {}/{}: {:?}", i + 1, path.len(), direction);
+            let step = executed.len() + 1;
+
+            if self.config.track_target && next + 1 == path.len() {
+                Self::confirm_target_ahead(&mut sensor_rx, moved_at, direction).await?;
+            }
+            log::debug!(
+                phase = "execution",
+                step = step,
+                direction:? = direction;
+                "executing step {}/{}: {:?}",
+                step,
+                executed.len() + path.len() - next,
+                direction
+            );
+
+            let moved = ros
+                .move_cmd_retry(direction, self.config.move_retries, MOVE_RETRY_BACKOFF)
+                .await?;
+            moved_at = Instant::now();
+            let destination = position.move_in_direction(direction, maze.bounds());
+
+            if !moved {
+                let (PlanUnknown::Open, Some(blocked)) = (self.config.plan_unknown, destination)
+                else {
+                    eyre::bail!("execution failed at step {}: {:?}", step, direction);
+                };
+
+                log::info!("{} is blocked, replanning from {}", blocked, position);
+                maze.set(blocked, Cell::Blocked);
+
+                let replan_start = Instant::now();
+                path = self
+                    .pathfinding
+                    .find_path(maze, position, target)
+                    .ok_or_else(|| eyre::eyre!("no path to target left after replanning"))?;
+                *total_planning_time += replan_start.elapsed();
+                next = 0;
+                continue;
+            }
 
-            let response = ros.move_cmd(*direction).await?;
-            if !response.success {
-                eyre::bail!("execution failed at step {}: {:?}", i + 1, direction);
+            position = destination
+                .ok_or_else(|| eyre::eyre!("move {:?} left the planned region", direction))?;
+            if let Some(guard) = &stray_guard {
+                guard.check(ros.as_ref(), position, step).await?;
             }
+            ros.publish_progress(step, maze.known_cells());
+            executed.push(direction);
+            next += 1;
         }
 
         log::info!("reached target");
-        Ok(path.len())
+        Ok(executed)
     }
 
+    /// before the final move, checks the target still sits where the path ends
+    async fn confirm_target_ahead(
+        sensor_rx: &mut watch::Receiver<Option<SensorFrame>>,
+        moved_at: Instant,
+        direction: MoveDirection,
+    ) -> eyre::Result<()> {
+        let sensors = sensors_since(sensor_rx, moved_at).await?.states;
+        if sensors.get(direction) != SensorState::Target {
+            eyre::bail!(
+                "target moved away: the final {:?} move would end on {:?} instead of the target",
+                direction,
+                sensors.get(direction)
+            );
+        }
+        Ok(())
+    }
+
+    /// confirms the robot is home by comparing fresh sensors with the ones recorded there
+    async fn verify_home(
+        &self,
+        ros: &impl MazeBackend,
+        sensor_cache: &HashMap<UnboundedPosition, SensorsStates>,
+        home: UnboundedPosition,
+    ) -> eyre::Result<()> {
+        let Some(expected) = sensor_cache.get(&home) else {
+            log::warn!("no sensors recorded at home, skipping return verification");
+            return Ok(());
+        };
+
+        let mut sensor_rx = ros.watch_sensors();
+        let actual =
+            Self::wait_for_sensors(&mut sensor_rx, Instant::now(), self.config.sensor_timeout)
+                .await?;
+
+        if &actual != expected {
+            eyre::bail!(
+                "sensors after returning home do not match {}: expected {:?}, got {:?}",
+                home,
+                expected,
+                actual
+            );
+        }
+
+        Ok(())
+    }
+
+    /// waits for the first sensor frame received at or after `since`, up to `timeout`
+    async fn wait_for_sensors(
+        sensor_rx: &mut watch::Receiver<Option<SensorFrame>>,
+        since: Instant,
+        timeout: Duration,
+    ) -> eyre::Result<SensorsStates> {
+        let wait_start = Instant::now();
+
+        match tokio::time::timeout(timeout, sensors_since(sensor_rx, since)).await {
+            Ok(Ok(frame)) => {
+                log::debug!(
+                    "first sensor frame (#{}) after {:?}",
+                    frame.seq,
+                    wait_start.elapsed()
+                );
+                Ok(frame.states)
+            }
+            Ok(Err(_)) => eyre::bail!("sensor channel closed while waiting for the first frame"),
+            Err(_) => eyre::bail!("no sensor data received within {:?}", timeout),
+        }
+    }
+
+    /// orthogonal direction leading straight onto the target, if it is one move away
+    fn adjacent_target_direction(sensors: &SensorsStates) -> Option<MoveDirection> {
+        MoveDirection::all()
+            .into_iter()
+            .find(|direction| sensors.get(*direction) == SensorState::Target)
+    }
+
+    /// what the sensors read at `pos`, if it is one of the eight cells they cover from `current`
+    fn reading_at(
+        current: UnboundedPosition,
+        pos: UnboundedPosition,
+        sensors: &SensorsStates,
+    ) -> Option<SensorState> {
+        let offset = (pos.row - current.row, pos.col - current.col);
+        sensors
+            .readings()
+            .into_iter()
+            .find_map(|(reading_offset, state)| (reading_offset == offset).then_some(state))
+    }
+
+    /// the target's position if a sensor sees it and the robot can walk there
+    ///
+    /// cardinal sightings are always reachable; a diagonal one only counts when at least one of
+    /// the two cardinal cells in between is free, otherwise the target was glimpsed past a wall
+    /// corner and planning over it would find no 4-connected path
     fn detect_target_in_sensors(
         current: UnboundedPosition,
-        sensors: &crate::ros::types::SensorsStates,
+        sensors: &SensorsStates,
     ) -> Option<UnboundedPosition> {
-        use crate::ros::types::SensorState;
-
-        let directions = [
-            (&sensors.up, -1isize, 0isize),
-            (&sensors.down, 1, 0),
-            (&sensors.left, 0, -1),
-            (&sensors.right, 0, 1),
-            (&sensors.up_left, -1, -1),
-            (&sensors.up_right, -1, 1),
-            (&sensors.down_left, 1, -1),
-            (&sensors.down_right, 1, 1),
-        ];
-
-        for (state, dr, dc) in directions {
-            if matches!(state, SensorState::Target) {
-                let new_row = current.row + dr;
-                let new_col = current.col + dc;
-                return Some(UnboundedPosition::new(new_row, new_col));
-            }
-        }
+        let reachable = |direction: MoveDirection| {
+            let (dr, dc) = direction.offset();
+            let vertical = if dr < 0 {
+                MoveDirection::Up
+            } else {
+                MoveDirection::Down
+            };
+            let horizontal = if dc < 0 {
+                MoveDirection::Left
+            } else {
+                MoveDirection::Right
+            };
 
-        None
+            !direction.is_diagonal()
+                || sensors.get(vertical) == SensorState::Free
+                || sensors.get(horizontal) == SensorState::Free
+        };
+
+        MoveDirection::all()
+            .into_iter()
+            .chain(MoveDirection::diagonals())
+            .find(|&direction| {
+                sensors.get(direction) == SensorState::Target && reachable(direction)
+            })
+            .map(|direction| current.move_in_direction(direction))
     }
 }
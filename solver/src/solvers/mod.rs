@@ -1,5 +1,12 @@
 mod blind;
 mod omniscient;
+mod path_cache;
+mod return_home;
+mod stray;
 
-pub use blind::BlindSolver;
-pub use omniscient::OmniscientSolver;
+/// moves a single exploration or execution may make before it is treated as a runaway
+pub const DEFAULT_MAX_STEPS: usize = 10_000;
+
+pub use blind::{BlindConfig, BlindSolver, PlanUnknown};
+pub use omniscient::{OmniscientConfig, OmniscientSolver};
+pub use path_cache::PathCache;
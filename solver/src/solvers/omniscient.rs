@@ -1,32 +1,80 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crate::{maze::BoundedMaze, ros::ROSInterface};
+use tokio::sync::watch;
 
-use crate::algorithms::pathfinding::{PathResult, PathfindingAlgorithm};
+use crate::{
+    backend::{MOVE_RETRY_BACKOFF, MazeBackend, SensorFrame, sensors_since},
+    maze::{BoundedMaze, Cell, CornerPolicy, Position},
+    ros::types::{MoveDirection, SensorState},
+};
+
+use super::{path_cache::PathCache, return_home::drive_home, stray::StrayGuard};
+use crate::algorithms::pathfinding::{PathResult, PathfindingAlgorithm, path};
+
+/// runtime knobs shared by every omniscient solver regardless of algorithm choice
+#[derive(Debug, Clone)]
+pub struct OmniscientConfig {
+    /// delay between moves
+    pub delay: Duration,
+    /// drive the executed path back to its start after reaching the target
+    pub return_home: bool,
+    /// compare live sensors against the fetched map after every move
+    pub verify_sensors: bool,
+    /// largest map accepted from get_map
+    pub max_cells: usize,
+    /// how long to wait for get_map to return a complete maze
+    pub map_timeout: Duration,
+    /// check the robot's real cell after every move and abort once it leaves the path
+    pub abort_on_stray: bool,
+    /// plan 8-connected with this corner policy, `None` keeps to cardinal moves
+    pub diagonal: Option<CornerPolicy>,
+    /// extra attempts for a move the simulator refuses before the run fails
+    pub move_retries: usize,
+    /// straighten the planned path before executing it, see `path::straighten`
+    pub smooth: bool,
+    /// stop after planning, never moving the robot, with a zero execution time
+    pub plan_only: bool,
+    /// load the planned path from, and store it in, this directory keyed by the maze's hash
+    pub path_cache: Option<PathBuf>,
+    /// reject maps with unrecognized cell symbols, or without a robot or target, up front
+    pub strict_map: bool,
+    /// longest path that will be executed, longer plans are refused before the robot moves
+    pub max_steps: usize,
+}
 
 pub struct OmniscientSolver<A: PathfindingAlgorithm> {
     algorithm: A,
-    delay: Duration,
+    config: OmniscientConfig,
 }
 
 impl<A: PathfindingAlgorithm> OmniscientSolver<A> {
-    pub fn new(algorithm: A, delay_ms: u64) -> Self {
-        Self {
-            algorithm,
-            delay: Duration::from_millis(delay_ms),
-        }
+    pub fn new(algorithm: A, config: OmniscientConfig) -> Self {
+        Self { algorithm, config }
     }
 
-    pub async fn solve(&self, ros: Arc<ROSInterface>) -> eyre::Result<PathResult> {
+    pub async fn solve<B: MazeBackend>(&self, ros: Arc<B>) -> eyre::Result<PathResult> {
         log::debug!("fetching maze map");
-        let map_response = ros.get_map().await?;
+        let map_response = ros.get_ready_map(self.config.map_timeout).await?;
 
-        let maze = BoundedMaze::from_flattened(
+        let parse = if self.config.strict_map {
+            BoundedMaze::from_flattened_strict
+        } else {
+            BoundedMaze::from_flattened_limited
+        };
+        let mut maze = parse(
             map_response.occupancy_grid_flattened,
             map_response.occupancy_grid_shape,
+            self.config.max_cells,
         )?;
+        if let Some(policy) = self.config.diagonal {
+            log::debug!("planning with diagonal moves ({:?} corners)", policy);
+            maze = maze.with_diagonal_moves(policy);
+        }
 
+        // each cell holds one symbol, so start and target always differ: a robot standing on the
+        // target hides it, and get_ready_map has already refused that map
         let start = maze
             .find_robot()
             .ok_or_else(|| eyre::eyre!("robot not found in maze"))?;
@@ -35,44 +83,237 @@ impl<A: PathfindingAlgorithm> OmniscientSolver<A> {
             .ok_or_else(|| eyre::eyre!("target not found in maze"))?;
 
         log::debug!(
-            "{}x{} maze: ({}, {}) → ({}, {})",
+            "{}x{} maze: {} → {}",
             maze.height(),
             maze.width(),
-            start.row,
-            start.col,
-            target.row,
-            target.col
+            start,
+            target
         );
 
+        // the flood fill follows the maze's own moves, diagonal ones included when enabled
+        if !maze.reachable_from(start).contains(&target) {
+            eyre::bail!("target at {} is unreachable from {}", target, start);
+        }
+
         let planning_start = Instant::now();
-        let path = self
-            .algorithm
-            .find_path(&maze, start, target)
-            .ok_or_else(|| eyre::eyre!("no path found"))?;
+        let cache = self.config.path_cache.as_deref().map(PathCache::new);
+        let cached = cache
+            .as_ref()
+            .and_then(|cache| cache.load(&maze, &self.algorithm.cache_key()));
+        let (path, nodes_expanded) = match cached {
+            Some(path) => {
+                log::info!("loaded cached path for maze {:016x}", maze.content_hash());
+                (path, 0)
+            }
+            None => {
+                let outcome = self.algorithm.search(&maze, start, target);
+                let path = outcome.path.ok_or_else(|| eyre::eyre!("no path found"))?;
+                if let Some(cache) = &cache
+                    && let Err(e) = cache.store(&maze, &self.algorithm.cache_key(), &path)
+                {
+                    log::warn!("failed to cache the planned path: {}", e);
+                }
+                (path, outcome.nodes_expanded)
+            }
+        };
         let planning_time = planning_start.elapsed();
 
         log::info!("planned {} steps in {:?}", path.len(), planning_time);
+        log::debug!("expanded {} nodes", nodes_expanded);
+
+        let path = if self.config.smooth {
+            let smoothed = path::straighten(&maze, start, &path);
+            log::info!(
+                "smoothed {} steps with {} turns into {} steps with {} turns",
+                path.len(),
+                path::count_turns(&path),
+                smoothed.len(),
+                path::count_turns(&smoothed)
+            );
+            smoothed
+        } else {
+            path
+        };
+
+        if self.config.plan_only {
+            log::info!("plan only, skipping execution");
+            return Ok(PathResult::new(path.len(), planning_time, Duration::ZERO)
+                .with_path(&maze, start, &path)
+                .with_nodes(nodes_expanded));
+        }
+
+        if path.len() > self.config.max_steps {
+            eyre::bail!(
+                "planned path has too many steps ({}, limit {})",
+                path.len(),
+                self.config.max_steps
+            );
+        }
 
         log::debug!("executing");
         let execution_start = Instant::now();
+        let mut sensor_rx = self.config.verify_sensors.then(|| ros.watch_sensors());
+        let mut position = start;
+        let stray_guard = if self.config.abort_on_stray {
+            Some(StrayGuard::new(ros.as_ref(), start).await?)
+        } else {
+            None
+        };
 
-        for (step, &direction) in path.iter().enumerate() {
-            if self.delay.as_millis() > 0 {
-                tokio::time::sleep(self.delay).await;
+        let continuous = ros.supports_continuous_moves();
+        let mut step = 0;
+        while step < path.len() {
+            let direction = path[step];
+            // a straight run that stops at a wall can go out as one continuous move, anything
+            // else would carry the robot past the turn
+            let run = path[step..].iter().take_while(|&&d| d == direction).count();
+            let cells =
+                if continuous && run > 1 && Self::run_ends_at_wall(&maze, position, direction, run)
+                {
+                    run
+                } else {
+                    1
+                };
+
+            if self.config.delay.as_millis() > 0 {
+                tokio::time::sleep(self.config.delay).await;
             }
 
-            log::debug!("step {}/{}: {:?}", step + 1, path.len(), direction);
-            let response = ros.move_cmd(direction).await?;
+            log::debug!(
+                phase = "execution",
+                step = step + 1,
+                direction:? = direction;
+                "step {}/{}: {:?} x{}",
+                step + 1,
+                path.len(),
+                direction,
+                cells
+            );
+            let moved = if cells > 1 {
+                let traversed = ros.move_until_wall(direction).await?;
+                if traversed != cells {
+                    eyre::bail!(
+                        "continuous move at step {} traversed {} cells instead of {}: {:?}",
+                        step + 1,
+                        traversed,
+                        cells,
+                        direction
+                    );
+                }
+                true
+            } else {
+                ros.move_cmd_retry(direction, self.config.move_retries, MOVE_RETRY_BACKOFF)
+                    .await?
+            };
+            let moved_at = Instant::now();
 
-            if !response.success {
+            if !moved {
                 eyre::bail!("move failed at step {}: {:?}", step + 1, direction);
             }
+
+            for _ in 0..cells {
+                if let Some(next) = position.move_in_direction(direction, maze.bounds()) {
+                    position = next;
+                }
+            }
+            step += cells;
+            if let Some(sensor_rx) = sensor_rx.as_mut() {
+                Self::verify_sensors(sensor_rx, moved_at, &maze, position, step).await?;
+            }
+            if let Some(guard) = &stray_guard {
+                guard.check(ros.as_ref(), position, step).await?;
+            }
+            ros.publish_progress(step, maze.known_cells());
+        }
+
+        log::info!("reached target");
+
+        let mut steps = path.len();
+        if self.config.return_home {
+            steps += drive_home(
+                ros.as_ref(),
+                &path,
+                self.config.delay,
+                self.config.move_retries,
+            )
+            .await?;
+            self.verify_home(ros.as_ref(), start).await?;
+            log::info!("back home at {}", start);
         }
 
         let execution_time = execution_start.elapsed();
 
-        log::info!("reached target");
+        Ok(PathResult::new(steps, planning_time, execution_time)
+            .with_path(&maze, start, &path)
+            .with_nodes(nodes_expanded))
+    }
+
+    /// whether `run` moves in `direction` from `from` end right before a wall or the maze's
+    /// edge, so that moving until blocked stops exactly there
+    fn run_ends_at_wall(
+        maze: &BoundedMaze,
+        from: Position,
+        direction: MoveDirection,
+        run: usize,
+    ) -> bool {
+        if direction.is_diagonal() {
+            return false;
+        }
+        let (dr, dc) = direction.offset();
+        let end = from + (dr * run as isize, dc * run as isize);
+        end.and_then(|end| end.move_in_direction(direction, maze.bounds()))
+            .is_none_or(|past| !maze.is_walkable(past))
+    }
+
+    /// warns when the live sensors around `position` disagree with the fetched map
+    async fn verify_sensors(
+        sensor_rx: &mut watch::Receiver<Option<SensorFrame>>,
+        moved_at: Instant,
+        maze: &BoundedMaze,
+        position: Position,
+        step: usize,
+    ) -> eyre::Result<()> {
+        let sensors = sensors_since(sensor_rx, moved_at).await?.states;
 
-        Ok(PathResult::new(path.len(), planning_time, execution_time))
+        let mismatch = sensors
+            .readings()
+            .into_iter()
+            .find_map(|((dr, dc), actual)| {
+                let row = position.row.checked_add_signed(dr)?;
+                let col = position.col.checked_add_signed(dc)?;
+                let expected = match maze.get(Position::new(row, col)) {
+                    Some(Cell::Free | Cell::Slow | Cell::Robot) => SensorState::Free,
+                    Some(Cell::Target) => SensorState::Target,
+                    Some(Cell::Blocked | Cell::Unknown) | None => SensorState::Blocked,
+                };
+                (expected != actual).then_some((Position::new(row, col), expected, actual))
+            });
+
+        if let Some((cell, expected, actual)) = mismatch {
+            log::warn!(
+                "sensor mismatch after step {} at {}: map says {:?}, sensors read {:?}",
+                step,
+                cell,
+                expected,
+                actual
+            );
+        }
+        Ok(())
+    }
+
+    /// confirms through the map that the robot is back where it started
+    async fn verify_home(&self, ros: &impl MazeBackend, start: Position) -> eyre::Result<()> {
+        let map_response = ros.get_map().await?;
+        let maze = BoundedMaze::from_flattened_limited(
+            map_response.occupancy_grid_flattened,
+            map_response.occupancy_grid_shape,
+            self.config.max_cells,
+        )?;
+
+        match maze.find_robot() {
+            Some(pos) if pos == start => Ok(()),
+            Some(pos) => eyre::bail!("robot ended at {} instead of home {}", pos, start),
+            None => eyre::bail!("robot not found in maze after returning home"),
+        }
     }
 }
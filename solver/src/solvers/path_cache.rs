@@ -0,0 +1,86 @@
+use std::path::{Path, PathBuf};
+
+use crate::{maze::BoundedMaze, ros::types::MoveDirection};
+
+/// planned paths stored as json files in one directory, named by the maze's content hash and
+/// the `PathfindingAlgorithm::cache_key` of the algorithm that planned them
+pub struct PathCache {
+    dir: PathBuf,
+}
+
+impl PathCache {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn entry(&self, maze: &BoundedMaze, algorithm: &str) -> PathBuf {
+        let algorithm: String = algorithm
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        self.dir
+            .join(format!("{:016x}-{}.json", maze.content_hash(), algorithm))
+    }
+
+    /// the cached path, `None` on a miss or an unreadable entry
+    pub fn load(&self, maze: &BoundedMaze, algorithm: &str) -> Option<Vec<MoveDirection>> {
+        let entry = self.entry(maze, algorithm);
+        let contents = std::fs::read_to_string(&entry).ok()?;
+
+        serde_json::from_str(&contents)
+            .inspect_err(|e| {
+                log::warn!(
+                    "ignoring corrupt path cache entry {}: {}",
+                    entry.display(),
+                    e
+                )
+            })
+            .ok()
+    }
+
+    pub fn store(
+        &self,
+        maze: &BoundedMaze,
+        algorithm: &str,
+        path: &[MoveDirection],
+    ) -> eyre::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.entry(maze, algorithm), serde_json::to_string(path)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithms::pathfinding::{AStar, Heuristic, PathfindingAlgorithm},
+        maze::{Cell, Position},
+    };
+
+    #[test]
+    fn stored_path_loads_back_only_for_the_same_maze_and_variant() {
+        let dir = std::env::temp_dir().join(format!("path-cache-test-{}", std::process::id()));
+        let cache = PathCache::new(&dir);
+        let maze = BoundedMaze::from_ascii("bbbbb\nbrftb\nbbbbb\n").unwrap();
+        let key = AStar::default().cache_key();
+        let path = [MoveDirection::Right, MoveDirection::Right];
+
+        assert_eq!(cache.load(&maze, &key), None);
+        cache.store(&maze, &key, &path).unwrap();
+        assert_eq!(cache.load(&maze, &key).as_deref(), Some(&path[..]));
+
+        let euclidean = AStar::new(Heuristic::Euclidean).cache_key();
+        assert_ne!(euclidean, key);
+        assert_eq!(cache.load(&maze, &euclidean), None);
+
+        let mut changed = maze.clone();
+        changed.set(Position::new(1, 2), Cell::Slow);
+        assert_eq!(cache.load(&changed, &key), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
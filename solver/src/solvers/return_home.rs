@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use crate::{
+    backend::{MOVE_RETRY_BACKOFF, MazeBackend},
+    ros::types::MoveDirection,
+};
+
+/// drives `path` backwards (reversed order, every move inverted), returning the moves made;
+/// a refused move is retried like on the way out
+pub(super) async fn drive_home(
+    ros: &impl MazeBackend,
+    path: &[MoveDirection],
+    delay: Duration,
+    move_retries: usize,
+) -> eyre::Result<usize> {
+    log::info!("returning home ({} steps)", path.len());
+
+    for (i, direction) in path.iter().rev().map(MoveDirection::opposite).enumerate() {
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+
+        log::debug!(
+            phase = "return",
+            step = i + 1,
+            direction:? = direction;
+            "return step {}/{}: {:?}",
+            i + 1,
+            path.len(),
+            direction
+        );
+
+        let moved = ros
+            .move_cmd_retry(direction, move_retries, MOVE_RETRY_BACKOFF)
+            .await?;
+        if !moved {
+            eyre::bail!("return home failed at step {}: {:?}", i + 1, direction);
+        }
+    }
+
+    Ok(path.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        maze::{BoundedMaze, Position},
+        mock::MockBackend,
+    };
+
+    #[tokio::test]
+    async fn drive_home_retries_refused_moves() {
+        let backend =
+            MockBackend::new(BoundedMaze::from_ascii("bbbbb\nbrftb\nbbbbb\n").unwrap()).unwrap();
+        assert!(backend.move_cmd(MoveDirection::Right).await.unwrap());
+
+        backend.refuse_next_moves(2);
+        let steps = drive_home(backend.as_ref(), &[MoveDirection::Right], Duration::ZERO, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(steps, 1);
+        assert_eq!(backend.robot(), Position::new(1, 1));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    backend::MazeBackend,
+    maze::{BoundedMaze, Position},
+};
+
+/// watches the robot's real cell through get_map during execution and fails on the first step
+/// where it is not where the plan put it
+///
+/// works in any planning coordinates: only the offset from the plan's start is compared
+pub(super) struct StrayGuard {
+    /// robot's cell in the simulator's map when execution began
+    origin: Position,
+    plan_start: Position,
+}
+
+impl StrayGuard {
+    pub(super) async fn new(ros: &impl MazeBackend, plan_start: Position) -> eyre::Result<Self> {
+        Ok(Self {
+            origin: robot_cell(ros).await?,
+            plan_start,
+        })
+    }
+
+    pub(super) async fn check(
+        &self,
+        ros: &impl MazeBackend,
+        planned: Position,
+        step: usize,
+    ) -> eyre::Result<()> {
+        let actual = robot_cell(ros).await?;
+        let expected = self
+            .origin
+            .row
+            .checked_add_signed(planned.row as isize - self.plan_start.row as isize)
+            .zip(
+                self.origin
+                    .col
+                    .checked_add_signed(planned.col as isize - self.plan_start.col as isize),
+            )
+            .map(|(row, col)| Position::new(row, col));
+
+        if expected != Some(actual) {
+            eyre::bail!(
+                "robot strayed from the plan at step {}: expected at {}, actually at {}",
+                step,
+                expected.map_or_else(|| "off the map".to_string(), |pos| pos.to_string()),
+                actual
+            );
+        }
+        Ok(())
+    }
+}
+
+async fn robot_cell(ros: &impl MazeBackend) -> eyre::Result<Position> {
+    let response = ros.get_map().await?;
+    BoundedMaze::from_flattened(
+        response.occupancy_grid_flattened,
+        response.occupancy_grid_shape,
+    )?
+    .find_robot()
+    .ok_or_else(|| eyre::eyre!("robot not found in maze while checking for strays"))
+}
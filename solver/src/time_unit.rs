@@ -0,0 +1,43 @@
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+/// unit every reported duration is printed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum TimeUnit {
+    /// whatever unit suits each value (ns, µs, ms or s), as `Duration`'s debug output does
+    #[default]
+    Auto,
+    Ns,
+    Us,
+    Ms,
+    S,
+}
+
+static TIME_UNIT: OnceLock<TimeUnit> = OnceLock::new();
+
+impl TimeUnit {
+    /// configured once at startup, like the logger
+    pub fn init(self) {
+        let _ = TIME_UNIT.set(self);
+    }
+}
+
+/// a `Duration` displayed in the configured unit, honoring width and alignment
+pub struct Elapsed(pub Duration);
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs_f64();
+        let text = match TIME_UNIT.get().copied().unwrap_or_default() {
+            TimeUnit::Auto => format!("{:?}", self.0),
+            TimeUnit::Ns => format!("{}ns", self.0.as_nanos()),
+            TimeUnit::Us => format!("{:.3}µs", secs * 1e6),
+            TimeUnit::Ms => format!("{:.3}ms", secs * 1e3),
+            TimeUnit::S => format!("{:.3}s", secs),
+        };
+        f.pad(&text)
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{algorithms::pathfinding::PathResult, maze::Position, ros::types::MoveDirection};
+
+/// bump whenever the on-disk layout of `TraceFile` changes
+pub const TRACE_VERSION: u32 = 1;
+
+/// recorded run: the maze it happened on, where it started and every move made
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceFile {
+    pub version: u32,
+    /// (height, width) of the maze the positions refer to
+    pub maze_dims: (usize, usize),
+    pub start: TracePosition,
+    pub steps: Vec<TraceStep>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TracePosition {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// move direction as sent to the simulator (`up`, `down`, `left`, `right`)
+    pub direction: String,
+    /// position after the move
+    pub position: TracePosition,
+}
+
+/// flat summary of one solve for external tools: endpoints, the moves in order and timings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunExport {
+    pub start: TracePosition,
+    pub target: TracePosition,
+    pub path: Vec<MoveDirection>,
+    pub steps: usize,
+    pub planning_us: u64,
+    pub execution_us: u64,
+    pub total_us: u64,
+    pub nodes_expanded: usize,
+}
+
+/// only the version field, so mismatched files are rejected before the rest is parsed
+#[derive(Deserialize)]
+struct TraceHeader {
+    version: u32,
+}
+
+impl From<Position> for TracePosition {
+    fn from(pos: Position) -> Self {
+        Self {
+            row: pos.row,
+            col: pos.col,
+        }
+    }
+}
+
+impl TraceFile {
+    pub fn from_result(result: &PathResult) -> Self {
+        let start = result
+            .waypoints
+            .first()
+            .copied()
+            .unwrap_or(Position::new(0, 0));
+        let steps = result
+            .path
+            .iter()
+            .zip(result.waypoints.iter().skip(1))
+            .map(|(direction, position)| TraceStep {
+                direction: direction.as_str().to_string(),
+                position: (*position).into(),
+            })
+            .collect();
+
+        Self {
+            version: TRACE_VERSION,
+            maze_dims: result.maze_dims,
+            start: start.into(),
+            steps,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+
+        let header: TraceHeader = serde_json::from_str(&contents)
+            .map_err(|e| eyre::eyre!("{}: not a trace file: {}", path.display(), e))?;
+        if header.version != TRACE_VERSION {
+            eyre::bail!(
+                "{}: trace format version {} is not supported (expected {})",
+                path.display(),
+                header.version,
+                TRACE_VERSION
+            );
+        }
+
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+impl RunExport {
+    /// the target is taken from the planning maze when the result carries one, otherwise it is
+    /// wherever the path ends
+    pub fn from_result(result: &PathResult) -> Self {
+        let start = result
+            .waypoints
+            .first()
+            .copied()
+            .unwrap_or(Position::new(0, 0));
+        let target = result
+            .maze
+            .as_ref()
+            .and_then(|maze| maze.find_target())
+            .or_else(|| result.waypoints.last().copied())
+            .unwrap_or(start);
+
+        Self {
+            start: start.into(),
+            target: target.into(),
+            path: result.path.clone(),
+            steps: result.steps,
+            planning_us: result.planning_time.as_micros() as u64,
+            execution_us: result.execution_time.as_micros() as u64,
+            total_us: result.total_time.as_micros() as u64,
+            nodes_expanded: result.nodes_expanded,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::maze::BoundedMaze;
+
+    #[test]
+    fn saved_trace_loads_back_unchanged() {
+        let maze = BoundedMaze::from_ascii("bbbbb\nbrffb\nbbbtb\nbbbbb\n").unwrap();
+        let start = maze.find_robot().unwrap();
+        let path = [
+            MoveDirection::Right,
+            MoveDirection::Right,
+            MoveDirection::Down,
+        ];
+        let result = PathResult::new(path.len(), Duration::ZERO, Duration::ZERO)
+            .with_path(&maze, start, &path);
+        let trace = TraceFile::from_result(&result);
+        let file = std::env::temp_dir().join(format!("trace-{}.json", std::process::id()));
+
+        trace.save(&file).unwrap();
+        let loaded = TraceFile::load(&file);
+        std::fs::remove_file(&file).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded, trace);
+        assert_eq!(loaded.maze_dims, (4, 5));
+        assert_eq!(loaded.start, TracePosition { row: 1, col: 1 });
+        assert_eq!(loaded.steps.len(), 3);
+        assert_eq!(loaded.steps[2].direction, "down");
+        assert_eq!(loaded.steps[2].position, TracePosition { row: 2, col: 3 });
+    }
+}
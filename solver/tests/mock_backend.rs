@@ -0,0 +1,394 @@
+use std::time::Duration;
+
+use solver::{
+    MazeBackend, Solver,
+    algorithms::{
+        exploration::{RecursiveBacktracker, WallFollower},
+        pathfinding::{AStar, BFS, Heuristic},
+    },
+    maze::{BoundedMaze, CornerPolicy, Position},
+    mock::MockBackend,
+    ros::types::MoveDirection,
+    solvers::{BlindConfig, BlindSolver, DEFAULT_MAX_STEPS, OmniscientConfig, PlanUnknown},
+};
+
+/// the short way runs along the top and down the right; the left branch is a dead end
+const MAZE: &str = "\
+bbbbbbb
+brffffb
+bfbbbfb
+bfffbtb
+bbbbbbb
+";
+
+const TARGET: Position = Position::new(3, 5);
+
+fn omniscient_config() -> OmniscientConfig {
+    OmniscientConfig {
+        delay: Duration::ZERO,
+        return_home: false,
+        verify_sensors: true,
+        max_cells: 1024,
+        map_timeout: Duration::from_secs(1),
+        abort_on_stray: true,
+        diagonal: None,
+        move_retries: 0,
+        smooth: false,
+        plan_only: false,
+        path_cache: None,
+        strict_map: true,
+        max_steps: DEFAULT_MAX_STEPS,
+    }
+}
+
+fn blind_config() -> BlindConfig {
+    BlindConfig {
+        delay: Duration::ZERO,
+        sensor_timeout: Duration::from_secs(1),
+        reset_before_exec: true,
+        max_extent: None,
+        return_home: false,
+        tolerate_blocked_moves: false,
+        plan_unknown: PlanUnknown::Wall,
+        track_target: false,
+        save_explored: None,
+        load_explored: None,
+        abort_on_stray: false,
+        dump_map: false,
+        move_retries: 0,
+        max_steps: DEFAULT_MAX_STEPS,
+    }
+}
+
+#[tokio::test]
+async fn omniscient_solve_reaches_the_target() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let solver = Solver::new(backend.clone());
+
+    let result = solver
+        .solve_omniscient(AStar::default(), omniscient_config())
+        .await
+        .unwrap();
+
+    assert_eq!(result.steps, 6);
+    assert_eq!(backend.robot(), TARGET);
+    assert_eq!(backend.moves(), 6);
+}
+
+#[tokio::test]
+async fn blind_solve_explores_then_reaches_the_target() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let solver = Solver::new(backend.clone());
+
+    let result = solver
+        .solve_blind(
+            RecursiveBacktracker::with_priority(MoveDirection::all()),
+            BFS,
+            blind_config(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.robot(), TARGET);
+    // exploration moves come on top of the six executed after the reset
+    assert!(result.steps > 6);
+    assert_eq!(result.path.len(), 6);
+}
+
+#[tokio::test]
+async fn blind_solve_with_the_target_next_to_the_start() {
+    let maze = BoundedMaze::from_ascii("bbbb\nbrtb\nbbbb\n").unwrap();
+    let backend = MockBackend::new(maze.clone()).unwrap();
+    let solver = Solver::new(backend.clone());
+
+    let result = solver
+        .solve_blind(
+            RecursiveBacktracker::with_priority(MoveDirection::all()),
+            BFS,
+            blind_config(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.robot(), Position::new(1, 2));
+    assert_eq!(result.steps, 1);
+    assert_eq!(result.path, [MoveDirection::Right]);
+    assert_eq!(result.waypoints.len(), 2);
+
+    // the shortcut goes through the normal execution, so driving home still applies
+    let backend = MockBackend::new(maze).unwrap();
+    let config = BlindConfig {
+        return_home: true,
+        ..blind_config()
+    };
+    let result = Solver::new(backend.clone())
+        .solve_blind(
+            RecursiveBacktracker::with_priority(MoveDirection::all()),
+            BFS,
+            config,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.robot(), Position::new(1, 1));
+    assert_eq!(result.steps, 2);
+}
+
+#[tokio::test]
+async fn omniscient_solve_without_a_target_points_at_the_map() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii("bbbb\nbrfb\nbbbb\n").unwrap()).unwrap();
+    let solver = Solver::new(backend.clone());
+    let config = OmniscientConfig {
+        map_timeout: Duration::ZERO,
+        ..omniscient_config()
+    };
+
+    let Err(err) = solver.solve_omniscient(AStar::default(), config).await else {
+        panic!("solved a maze without a target");
+    };
+
+    assert!(err.to_string().contains("0 targets"), "{}", err);
+    assert!(err.to_string().contains("may be malformed"), "{}", err);
+    assert_eq!(backend.moves(), 0);
+}
+
+#[tokio::test]
+async fn blind_solver_takes_an_explorer_and_a_pathfinder() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let mut solver = BlindSolver::new(WallFollower::new(), AStar::default(), blind_config());
+
+    let result = solver.solve(backend.clone()).await.unwrap();
+
+    assert_eq!(backend.robot(), TARGET);
+    assert_eq!(result.path.len(), 6);
+}
+
+#[tokio::test]
+async fn move_until_wall_falls_back_to_single_moves() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    assert!(!backend.supports_continuous_moves());
+
+    let traversed = backend.move_until_wall(MoveDirection::Right).await.unwrap();
+
+    assert_eq!(traversed, 4);
+    assert_eq!(backend.robot(), Position::new(1, 5));
+    // the move into the wall is the one extra request
+    assert_eq!(backend.requests(), 5);
+}
+
+#[tokio::test]
+async fn omniscient_solve_collapses_runs_that_end_at_a_wall() {
+    let backend =
+        MockBackend::with_continuous_moves(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let solver = Solver::new(backend.clone());
+
+    let result = solver
+        .solve_omniscient(AStar::default(), omniscient_config())
+        .await
+        .unwrap();
+
+    assert_eq!(result.steps, 6);
+    assert_eq!(backend.robot(), TARGET);
+    assert_eq!(backend.moves(), 6);
+    // both runs (four right, two down) stop at a wall, each costing one refused request
+    assert_eq!(backend.requests(), 8);
+}
+
+#[tokio::test]
+async fn blind_solve_with_the_target_first_seen_diagonally() {
+    // the start only glimpses the target up and to the right, past the wall on its right
+    let maze = BoundedMaze::from_ascii("bbbb\nbftb\nbrbb\nbbbb\n").unwrap();
+    let backend = MockBackend::new(maze).unwrap();
+
+    let result = Solver::new(backend.clone())
+        .solve_blind(
+            RecursiveBacktracker::with_priority(MoveDirection::all()),
+            BFS,
+            blind_config(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.robot(), Position::new(1, 2));
+    assert_eq!(result.path, [MoveDirection::Up, MoveDirection::Right]);
+}
+
+#[tokio::test]
+async fn a_low_max_steps_aborts_both_solvers() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let config = OmniscientConfig {
+        max_steps: 3,
+        ..omniscient_config()
+    };
+
+    let Err(err) = Solver::new(backend.clone())
+        .solve_omniscient(AStar::default(), config)
+        .await
+    else {
+        panic!("executed a 6 step path with a 3 step limit");
+    };
+
+    assert!(err.to_string().contains("limit 3"), "{}", err);
+    assert_eq!(backend.moves(), 0);
+
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let config = BlindConfig {
+        max_steps: 2,
+        ..blind_config()
+    };
+
+    let Err(err) = Solver::new(backend.clone())
+        .solve_blind(
+            RecursiveBacktracker::with_priority(MoveDirection::all()),
+            BFS,
+            config,
+        )
+        .await
+    else {
+        panic!("explored the maze within 2 steps");
+    };
+
+    assert!(err.to_string().contains("too many steps"), "{}", err);
+    assert!(err.to_string().contains("limit 2"), "{}", err);
+    assert_ne!(backend.robot(), TARGET);
+}
+
+#[tokio::test]
+async fn plan_only_reports_no_execution_time() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let config = OmniscientConfig {
+        plan_only: true,
+        ..omniscient_config()
+    };
+
+    let result = Solver::new(backend.clone())
+        .solve_omniscient(AStar::default(), config)
+        .await
+        .unwrap();
+
+    assert_eq!(result.steps, 6);
+    assert_eq!(result.execution_time, Duration::ZERO);
+    assert_eq!(result.total_time, result.planning_time);
+    assert_eq!(backend.moves(), 0);
+    assert_eq!(backend.robot(), Position::new(1, 1));
+}
+
+#[tokio::test]
+async fn orthogonally_isolated_target_needs_diagonal_moves() {
+    // the target's only walkable neighbour is the free cell up and to its left
+    let maze = BoundedMaze::from_ascii("bbbbb\nbrfbb\nbbbtb\nbbbbb\n").unwrap();
+    let target = Position::new(2, 3);
+
+    for diagonal in [
+        None,
+        Some(CornerPolicy::Forbid),
+        Some(CornerPolicy::AllowOneWall),
+    ] {
+        let backend = MockBackend::new(maze.clone()).unwrap();
+        let config = OmniscientConfig {
+            diagonal,
+            ..omniscient_config()
+        };
+
+        let Err(err) = Solver::new(backend.clone())
+            .solve_omniscient(AStar::default(), config)
+            .await
+        else {
+            panic!("reached an isolated target with {:?}", diagonal);
+        };
+
+        assert!(err.to_string().contains("unreachable"), "{}", err);
+        assert_eq!(backend.moves(), 0);
+    }
+
+    let backend = MockBackend::new(maze).unwrap();
+    let config = OmniscientConfig {
+        diagonal: Some(CornerPolicy::AllowBoth),
+        ..omniscient_config()
+    };
+
+    let result = Solver::new(backend.clone())
+        .solve_omniscient(AStar::new(Heuristic::Chebyshev), config)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.path,
+        [MoveDirection::Right, MoveDirection::DownRight]
+    );
+    assert_eq!(backend.robot(), target);
+}
+
+#[tokio::test]
+async fn blind_solve_plans_from_a_start_away_from_the_corner() {
+    // the robot starts at the dead end of the left branch, ten steps from the target
+    let maze = BoundedMaze::from_ascii(
+        "\
+bbbbbbb
+bfffffb
+bfbbbfb
+bffrbtb
+bbbbbbb
+",
+    )
+    .unwrap();
+
+    let backend = MockBackend::new(maze.clone()).unwrap();
+    let result = Solver::new(backend.clone())
+        .solve_blind(
+            RecursiveBacktracker::with_priority(MoveDirection::all()),
+            BFS,
+            blind_config(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(backend.robot(), TARGET);
+    assert_eq!(result.path.len(), 10);
+    assert_eq!(result.waypoints.first(), Some(&Position::new(3, 3)));
+    assert_eq!(result.waypoints.last(), Some(&TARGET));
+
+    // without the reset the plan starts wherever exploration stopped; the backtracker would
+    // have walked back to the start, the wall follower ends up by the target instead
+    let backend = MockBackend::new(maze).unwrap();
+    let config = BlindConfig {
+        reset_before_exec: false,
+        ..blind_config()
+    };
+    let result = Solver::new(backend.clone())
+        .solve_blind(WallFollower::new(), BFS, config)
+        .await
+        .unwrap();
+
+    assert_eq!(backend.robot(), TARGET);
+    assert!(result.path.len() < 10, "{:?}", result.path);
+    assert_ne!(result.waypoints.first(), Some(&Position::new(3, 3)));
+    assert_eq!(result.waypoints.last(), Some(&TARGET));
+}
+
+#[tokio::test]
+async fn omniscient_solve_with_the_robot_on_the_target_reports_the_hidden_target() {
+    let backend = MockBackend::new(BoundedMaze::from_ascii(MAZE).unwrap()).unwrap();
+    let solver = Solver::new(backend.clone());
+    solver
+        .solve_omniscient(AStar::default(), omniscient_config())
+        .await
+        .unwrap();
+    assert_eq!(backend.robot(), TARGET);
+
+    // the map now draws the robot over the target, so there is no target left to plan to
+    let config = OmniscientConfig {
+        map_timeout: Duration::ZERO,
+        ..omniscient_config()
+    };
+    let Err(err) = solver.solve_omniscient(AStar::default(), config).await else {
+        panic!("planned from the target to a target the map no longer shows");
+    };
+
+    assert!(
+        err.to_string().contains("1 robots and 0 targets"),
+        "{}",
+        err
+    );
+    assert_eq!(backend.moves(), 6);
+}